@@ -149,6 +149,8 @@ build_exceptions! {
     UnknownCatalog(1119),
     UnknownCatalogType(1120),
     UnmatchMaskPolicyReturnType(1121),
+    // The correlated subquery cannot be decorrelated.
+    UnsupportedCorrelatedSubquery(1122),
 
     // Data Related Errors
 
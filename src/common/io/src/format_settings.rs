@@ -12,11 +12,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::str::FromStr;
+
 use chrono_tz::Tz;
+use databend_common_exception::ErrorCode;
+
+use crate::constants::NULL_BYTES_UPPER;
+
+/// The largest precision accepted by `fixed(n)` and `scientific(n)`, a `f64` has
+/// at most 17 significant decimal digits.
+const MAX_FLOAT_PRECISION: usize = 17;
+
+/// How float values are rendered as text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// The shortest text that parses back to exactly the same value.
+    #[default]
+    Shortest,
+    /// A fixed number of digits after the decimal point, e.g. `fixed(2)` gives `0.10`.
+    Fixed(usize),
+    /// Scientific notation with a fixed number of digits after the decimal point,
+    /// e.g. `scientific(2)` gives `1.00e-1`.
+    Scientific(usize),
+}
+
+impl FromStr for FloatFormat {
+    type Err = ErrorCode;
+
+    /// Parses `shortest`, `fixed(n)` or `scientific(n)`, ignoring ASCII case, where `n`
+    /// is at most 17.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_ascii_lowercase();
+        if lower == "shortest" {
+            return Ok(FloatFormat::Shortest);
+        }
+        let precision = |prefix: &str| {
+            lower
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_prefix('('))
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|n| n.trim().parse::<usize>().ok())
+                .filter(|n| *n <= MAX_FLOAT_PRECISION)
+        };
+        if let Some(n) = precision("fixed") {
+            return Ok(FloatFormat::Fixed(n));
+        }
+        if let Some(n) = precision("scientific") {
+            return Ok(FloatFormat::Scientific(n));
+        }
+        Err(ErrorCode::BadArguments(format!(
+            "invalid float format '{s}', expect 'shortest', 'fixed(n)' or 'scientific(n)' with n <= {MAX_FLOAT_PRECISION}"
+        )))
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FormatSettings {
     pub timezone: Tz,
+    /// How a NULL is rendered as text, e.g. `NULL`, `\N` or an empty string.
+    /// A NULL sent as a MySQL column value is still a protocol NULL.
+    pub null_string: String,
+    pub float_format: FloatFormat,
+    /// Quote top-level strings and variants sent as MySQL text cells, e.g. `'it''s'`.
+    pub quote_strings: bool,
 }
 
 // only used for tests
@@ -24,6 +82,9 @@ impl Default for FormatSettings {
     fn default() -> Self {
         Self {
             timezone: "UTC".parse::<Tz>().unwrap(),
+            null_string: NULL_BYTES_UPPER.to_string(),
+            float_format: FloatFormat::default(),
+            quote_strings: false,
         }
     }
 }
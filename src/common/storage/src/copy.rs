@@ -148,8 +148,9 @@ pub enum FileParseError {
 }
 
 impl FileParseError {
+    /// `line` is the 0-based row index, it is reported 1-based like `first_error_line` of copy.
     pub fn to_error_code(&self, mode: &OnErrorMode, file_path: &str, line: usize) -> ErrorCode {
-        let pos: String = format!("at file '{}', line {}", file_path, line);
+        let pos: String = format!("at file '{}', line {}", file_path, line + 1);
         let message = match mode {
             OnErrorMode::AbortNum(n) if *n > 1u64 => {
                 format!("abort after {n} errors! the last error: {self}",)
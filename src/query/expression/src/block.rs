@@ -13,24 +13,42 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::fmt::Debug;
+use std::hash::BuildHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::io::Cursor;
 use std::ops::Range;
+use std::sync::Arc;
+use std::sync::OnceLock;
 
 use databend_common_arrow::arrow::array::Array;
+use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use databend_common_arrow::arrow::datatypes::Field as ArrowField;
+use databend_common_arrow::arrow::io::ipc::read::read_file_metadata;
+use databend_common_arrow::arrow::io::ipc::read::FileReader;
 use databend_common_arrow::ArrayRef;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::schema::DataSchema;
 use crate::types::AnyType;
 use crate::types::DataType;
+use crate::types::NumberScalar;
+use crate::utils::arrow::and_validities;
+use crate::utils::arrow::ArrowSpillWriter;
 use crate::Column;
 use crate::ColumnBuilder;
 use crate::DataSchemaRef;
 use crate::Domain;
 use crate::Scalar;
+use crate::ScalarRef;
 use crate::TableSchemaRef;
 use crate::Value;
 
@@ -38,12 +56,22 @@ pub type SendableDataBlockStream =
     std::pin::Pin<Box<dyn futures::stream::Stream<Item = Result<DataBlock>> + Send>>;
 pub type BlockMetaInfoPtr = Box<dyn BlockMetaInfo>;
 
+/// Version of the buffers written by [`DataBlock::to_ipc`], stored in their first byte.
+///
+/// Buffers written before the version prefix was added start directly with the arrow
+/// IPC magic and are read as version 0.
+pub const IPC_FORMAT_VERSION: u8 = 1;
+
+const ARROW_IPC_MAGIC: &[u8] = b"ARROW1";
+
 /// DataBlock is a lightweight container for a group of columns.
 #[derive(Clone)]
 pub struct DataBlock {
     columns: Vec<BlockEntry>,
     num_rows: usize,
     meta: Option<BlockMetaInfoPtr>,
+    // Lazily computed by `domains()`, reset whenever the columns are mutated.
+    domains: OnceLock<Vec<Domain>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -75,6 +103,19 @@ impl BlockEntry {
     }
 }
 
+/// Statistics of a single column in a [`DataBlock`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColumnStats {
+    /// Minimum non-null value, `Scalar::Null` if unknown or all values are NULL.
+    pub min: Scalar,
+    /// Maximum non-null value, `Scalar::Null` if unknown or all values are NULL.
+    pub max: Scalar,
+    pub null_count: usize,
+    /// Number of distinct non-null values, counted by value hashes, so it is exact
+    /// up to hash collisions.
+    pub distinct_count: usize,
+}
+
 #[typetag::serde(tag = "type")]
 pub trait BlockMetaInfo: Debug + Send + Sync + Any + 'static {
     #[allow(clippy::borrowed_box)]
@@ -101,6 +142,37 @@ impl<T: BlockMetaInfo> BlockMetaInfoDowncast for T {
     }
 }
 
+/// Block meta recording the input partition a block was read from, so that
+/// partition-preserving operators can route blocks after a shuffle.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionMeta {
+    pub partition_id: usize,
+}
+
+#[typetag::serde(name = "partition_meta")]
+impl BlockMetaInfo for PartitionMeta {
+    #[allow(clippy::borrowed_box)]
+    fn equals(&self, info: &Box<dyn BlockMetaInfo>) -> bool {
+        PartitionMeta::downcast_ref_from(info).is_some_and(|other| other == self)
+    }
+
+    fn clone_self(&self) -> Box<dyn BlockMetaInfo> {
+        Box::new(*self)
+    }
+}
+
+/// The hash function used by [`DataBlock::hash_columns`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashFunction {
+    /// A fast non-cryptographic hash. Its values are stable within a process,
+    /// but may differ between runs.
+    #[default]
+    Fast,
+    /// The same hash with a fixed seed, so the values are reproducible across
+    /// runs, e.g. in tests.
+    Seeded(u64),
+}
+
 impl DataBlock {
     #[inline]
     pub fn new(columns: Vec<BlockEntry>, num_rows: usize) -> Self {
@@ -120,16 +192,18 @@ impl DataBlock {
             columns,
             num_rows,
             meta,
+            domains: OnceLock::new(),
         }
     }
 
     fn check_columns_valid(columns: &[BlockEntry], num_rows: usize) -> Result<()> {
-        for entry in columns.iter() {
+        for (offset, entry) in columns.iter().enumerate() {
             if let Value::Column(c) = &entry.value {
                 c.check_valid()?;
                 if c.len() != num_rows {
                     return Err(ErrorCode::Internal(format!(
-                        "DataBlock corrupted, column length mismatch, col: {}, num_rows: {}",
+                        "DataBlock corrupted, column length mismatch, offset: {}, col: {}, num_rows: {}",
+                        offset,
                         c.len(),
                         num_rows
                     )));
@@ -139,6 +213,8 @@ impl DataBlock {
         Ok(())
     }
 
+    /// Validate that every column has `num_rows` rows, e.g. after mutating the
+    /// columns through [`DataBlock::columns_mut`].
     pub fn check_valid(&self) -> Result<()> {
         Self::check_columns_valid(&self.columns, self.num_rows)
     }
@@ -157,6 +233,35 @@ impl DataBlock {
         DataBlock::new(columns, num_rows)
     }
 
+    /// Like [`DataBlock::new_from_columns`], but returns an error instead of panicking
+    /// if the columns have different lengths.
+    pub fn try_new_from_columns(columns: Vec<Column>) -> Result<Self> {
+        let num_rows = columns.first().map(|c| c.len()).unwrap_or(0);
+        if let Some(c) = columns.iter().find(|c| c.len() != num_rows) {
+            return Err(ErrorCode::Internal(format!(
+                "column length mismatch, col: {}, num_rows: {}",
+                c.len(),
+                num_rows
+            )));
+        }
+
+        let columns = columns
+            .into_iter()
+            .map(|col| BlockEntry::new(col.data_type(), Value::Column(col)))
+            .collect();
+        Ok(DataBlock::new(columns, num_rows))
+    }
+
+    /// Builds a single-row block where every entry is a [`Value::Scalar`],
+    /// e.g. for evaluating constant expressions or a row of a VALUES clause.
+    pub fn from_scalars(scalars: Vec<(DataType, Scalar)>) -> Self {
+        let columns = scalars
+            .into_iter()
+            .map(|(data_type, scalar)| BlockEntry::new(data_type, Value::Scalar(scalar)))
+            .collect();
+        DataBlock::new(columns, 1)
+    }
+
     #[inline]
     pub fn empty() -> Self {
         DataBlock::new(vec![], 0)
@@ -193,14 +298,62 @@ impl DataBlock {
 
     #[inline]
     pub fn columns_mut(&mut self) -> &mut [BlockEntry] {
+        self.domains.take();
         &mut self.columns
     }
 
+    /// Panics if `offset` is out of bounds, use [`DataBlock::try_get_by_offset`]
+    /// when the offset is not guaranteed by the plan.
     #[inline]
     pub fn get_by_offset(&self, offset: usize) -> &BlockEntry {
         &self.columns[offset]
     }
 
+    pub fn try_get_by_offset(&self, offset: usize) -> Result<&BlockEntry> {
+        self.columns.get(offset).ok_or_else(|| {
+            ErrorCode::BadArguments(format!(
+                "column offset {} is out of bounds for a block of {} columns",
+                offset,
+                self.columns.len()
+            ))
+        })
+    }
+
+    /// The values of every column at row `index`.
+    pub fn row(&self, index: usize) -> Result<Vec<Scalar>> {
+        if index >= self.num_rows {
+            return Err(ErrorCode::BadArguments(format!(
+                "row index {} is out of bounds for a block of {} rows",
+                index, self.num_rows
+            )));
+        }
+        Ok(self
+            .columns
+            .iter()
+            .map(|entry| match &entry.value {
+                Value::Scalar(scalar) => scalar.clone(),
+                Value::Column(column) => column.index(index).unwrap().to_owned(),
+            })
+            .collect())
+    }
+
+    /// Iterate over the rows, yielding the values of every column as `ScalarRef`s.
+    ///
+    /// The values borrow from the block, so the block cannot be modified or dropped while
+    /// the rows are alive. Unlike calling [`DataBlock::row`] in a loop, no owned scalar is
+    /// created; call `to_owned` on a value that has to outlive the block.
+    pub fn iter_rows(&self) -> impl Iterator<Item = Vec<ScalarRef<'_>>> + '_ {
+        (0..self.num_rows).map(move |index| {
+            self.columns
+                .iter()
+                .map(|entry| match &entry.value {
+                    Value::Scalar(scalar) => scalar.as_ref(),
+                    Value::Column(column) => unsafe { column.index_unchecked(index) },
+                })
+                .collect()
+        })
+    }
+
     #[inline]
     pub fn num_rows(&self) -> usize {
         self.num_rows
@@ -223,11 +376,219 @@ impl DataBlock {
     }
 
     #[inline]
-    pub fn domains(&self) -> Vec<Domain> {
-        self.columns
-            .iter()
-            .map(|entry| entry.value.as_ref().domain(&entry.data_type))
-            .collect()
+    /// The domains are computed on the first call and cached for later calls.
+    pub fn domains(&self) -> &[Domain] {
+        self.domains.get_or_init(|| {
+            self.columns
+                .iter()
+                .map(|entry| entry.value.as_ref().domain(&entry.data_type))
+                .collect()
+        })
+    }
+
+    /// Combine the validities of all columns into one bitmap.
+    ///
+    /// With `all_columns` set, a row is valid only if it is non-null in every column;
+    /// otherwise a row is valid if it is non-null in any column. A NULL scalar entry
+    /// counts as NULL in every row. `None` means that all rows are valid.
+    pub fn validity_mask(&self, all_columns: bool) -> Option<Bitmap> {
+        let validities = self.columns.iter().map(|entry| match &entry.value {
+            Value::Scalar(Scalar::Null) | Value::Column(Column::Null { .. }) => {
+                Some(Bitmap::new_zeroed(self.num_rows))
+            }
+            Value::Scalar(_) => None,
+            Value::Column(Column::Nullable(col)) => Some(col.validity.clone()),
+            Value::Column(_) => None,
+        });
+
+        if all_columns {
+            validities.fold(None, and_validities)
+        } else {
+            let mut mask: Option<Bitmap> = None;
+            for validity in validities {
+                match validity {
+                    // A column without nulls makes every row valid.
+                    None => return None,
+                    Some(validity) => {
+                        mask = Some(match mask {
+                            Some(mask) => (&mask) | (&validity),
+                            None => validity,
+                        })
+                    }
+                }
+            }
+            mask
+        }
+    }
+
+    /// Whether both blocks hold the same data: the same number of rows, and columns
+    /// with equal types and values. A scalar entry equals a column repeating its value.
+    pub fn equals(&self, other: &DataBlock) -> bool {
+        self.equals_with_epsilon(other, 0.0)
+    }
+
+    /// Like [`DataBlock::equals`], but top-level float values may differ by up to `epsilon`.
+    pub fn equals_with_epsilon(&self, other: &DataBlock, epsilon: f64) -> bool {
+        fn value_eq(a: ScalarRef, b: ScalarRef, epsilon: f64) -> bool {
+            match (a, b) {
+                (
+                    ScalarRef::Number(NumberScalar::Float32(a)),
+                    ScalarRef::Number(NumberScalar::Float32(b)),
+                ) => a == b || (a.0 as f64 - b.0 as f64).abs() <= epsilon,
+                (
+                    ScalarRef::Number(NumberScalar::Float64(a)),
+                    ScalarRef::Number(NumberScalar::Float64(b)),
+                ) => a == b || (a.0 - b.0).abs() <= epsilon,
+                (a, b) => a == b,
+            }
+        }
+
+        self.num_rows == other.num_rows
+            && self.num_columns() == other.num_columns()
+            && self.columns.iter().zip(other.columns.iter()).all(|(a, b)| {
+                a.data_type == b.data_type
+                    && (0..self.num_rows).all(|row| {
+                        match (a.value.index(row), b.value.index(row)) {
+                            (Some(a), Some(b)) => value_eq(a, b, epsilon),
+                            _ => false,
+                        }
+                    })
+            })
+    }
+
+    /// Drop every row whose values in the key columns at `offsets` equal those of the
+    /// previous row, keeping the first row of each run.
+    ///
+    /// The block is expected to be sorted by the keys, so that this removes all duplicates.
+    /// NULL keys are equal to each other here.
+    pub fn dedup_consecutive(&self, offsets: &[usize]) -> Result<Self> {
+        let mut key_columns = Vec::with_capacity(offsets.len());
+        for offset in offsets {
+            // A scalar key is the same in every row and cannot tell rows apart.
+            if let Value::Column(column) = &self.try_get_by_offset(*offset)?.value {
+                key_columns.push(column);
+            }
+        }
+
+        let bitmap = (0..self.num_rows)
+            .map(|row| {
+                row == 0
+                    || key_columns.iter().any(|column| unsafe {
+                        column.index_unchecked(row) != column.index_unchecked(row - 1)
+                    })
+            })
+            .collect::<Bitmap>();
+        self.clone().filter_with_bitmap(&bitmap)
+    }
+
+    /// Combine the values of the columns at `offsets` into one hash per row.
+    ///
+    /// Rows with equal keys get equal hashes, regardless of whether a key column is
+    /// nullable or a scalar. NULL is hashed with its own tag so it differs from any value.
+    pub fn hash_columns(&self, offsets: &[usize], hash_function: HashFunction) -> Vec<u64> {
+        match hash_function {
+            HashFunction::Fast => {
+                static FAST_HASH_STATE: OnceLock<ahash::RandomState> = OnceLock::new();
+                self.hash_columns_with(
+                    offsets,
+                    FAST_HASH_STATE.get_or_init(ahash::RandomState::new),
+                )
+            }
+            HashFunction::Seeded(seed) => self.hash_columns_with(
+                offsets,
+                &ahash::RandomState::with_seeds(seed, seed, seed, seed),
+            ),
+        }
+    }
+
+    fn hash_columns_with<S: BuildHasher>(&self, offsets: &[usize], state: &S) -> Vec<u64> {
+        fn hash_value<H: Hasher>(value: ScalarRef, hasher: &mut H) {
+            match value {
+                ScalarRef::Null => 0u8.hash(hasher),
+                value => {
+                    1u8.hash(hasher);
+                    value.hash(hasher);
+                }
+            }
+        }
+
+        let mut hashers = (0..self.num_rows)
+            .map(|_| state.build_hasher())
+            .collect::<Vec<_>>();
+        for offset in offsets {
+            match &self.get_by_offset(*offset).value {
+                Value::Scalar(scalar) => {
+                    for hasher in hashers.iter_mut() {
+                        hash_value(scalar.as_ref(), hasher);
+                    }
+                }
+                Value::Column(col) => {
+                    for (value, hasher) in col.iter().zip(hashers.iter_mut()) {
+                        hash_value(value, hasher);
+                    }
+                }
+            }
+        }
+        hashers.iter().map(|hasher| hasher.finish()).collect()
+    }
+
+    /// Compute min/max (from the column's domain), null count and distinct count of
+    /// the column at `offset`.
+    ///
+    /// The domain is taken from [`DataBlock::domains`] if it is already cached. The
+    /// distinct count keeps a hash of every distinct value, which takes memory linear
+    /// in the number of distinct values of the column.
+    pub fn column_stats(&self, offset: usize) -> ColumnStats {
+        let entry = self.get_by_offset(offset);
+        if self.num_rows == 0 {
+            return ColumnStats {
+                min: Scalar::Null,
+                max: Scalar::Null,
+                null_count: 0,
+                distinct_count: 0,
+            };
+        }
+
+        let (min, max) = match self.domains.get() {
+            Some(domains) => domains[offset].to_minmax(),
+            None => entry.value.as_ref().domain(&entry.data_type).to_minmax(),
+        };
+        match &entry.value {
+            Value::Scalar(Scalar::Null) => ColumnStats {
+                min,
+                max,
+                null_count: self.num_rows,
+                distinct_count: 0,
+            },
+            Value::Scalar(_) => ColumnStats {
+                min,
+                max,
+                null_count: 0,
+                distinct_count: 1,
+            },
+            Value::Column(col) => {
+                let null_count = match col.validity() {
+                    (true, _) => self.num_rows,
+                    (false, Some(bitmap)) => bitmap.unset_bits(),
+                    (false, None) => 0,
+                };
+                let distinct_hashes = col
+                    .iter()
+                    .filter(|value| !matches!(value, ScalarRef::Null))
+                    .map(|value| {
+                        let mut hasher = DefaultHasher::new();
+                        value.hash(&mut hasher);
+                        hasher.finish()
+                    })
+                    .collect::<HashSet<_>>();
+                ColumnStats {
+                    min,
+                    max,
+                    null_count,
+                    distinct_count: distinct_hashes.len(),
+                }
+            }
+        }
     }
 
     #[inline]
@@ -235,26 +596,51 @@ impl DataBlock {
         self.columns().iter().map(|entry| entry.memory_size()).sum()
     }
 
+    /// Average number of bytes per row, or 0 if the block has no rows.
+    ///
+    /// Unlike [`DataBlock::memory_size`], a scalar entry is counted as if it was
+    /// expanded to every row, so the estimate doesn't depend on the entry kind.
+    pub fn avg_row_size(&self) -> usize {
+        if self.num_rows == 0 {
+            return 0;
+        }
+        let total: usize = self
+            .columns
+            .iter()
+            .map(|entry| match &entry.value {
+                Value::Scalar(s) => s.as_ref().memory_size() * self.num_rows,
+                Value::Column(c) => c.memory_size(),
+            })
+            .sum();
+        total / self.num_rows
+    }
+
     pub fn convert_to_full(&self) -> Self {
+        // Fast path: nothing to densify, column data are shared with `self`.
+        if self
+            .columns
+            .iter()
+            .all(|entry| matches!(entry.value, Value::Column(_)))
+        {
+            return self.clone();
+        }
+
         let columns = self
             .columns()
             .iter()
             .map(|entry| match &entry.value {
                 Value::Scalar(s) => {
-                    let builder =
-                        ColumnBuilder::repeat(&s.as_ref(), self.num_rows, &entry.data_type);
-                    let col = builder.build();
+                    let col = Column::constant(&s.as_ref(), self.num_rows, &entry.data_type);
                     BlockEntry::new(entry.data_type.clone(), Value::Column(col))
                 }
-                Value::Column(c) => {
-                    BlockEntry::new(entry.data_type.clone(), Value::Column(c.clone()))
-                }
+                Value::Column(_) => entry.clone(),
             })
             .collect();
         Self {
             columns,
             num_rows: self.num_rows,
             meta: self.meta.clone(),
+            domains: OnceLock::new(),
         }
     }
 
@@ -276,6 +662,7 @@ impl DataBlock {
             columns,
             num_rows: range.end - range.start,
             meta: self.meta.clone(),
+            domains: OnceLock::new(),
         }
     }
 
@@ -329,8 +716,19 @@ impl DataBlock {
         res
     }
 
+    /// Remove the first `n` rows from the block and return them as a new block.
+    /// If `n` is not less than the number of rows, the whole block is returned and
+    /// `self` is left empty.
+    pub fn pop_front(&mut self, n: usize) -> Self {
+        let n = n.min(self.num_rows);
+        let front = self.slice(0..n);
+        *self = self.slice(n..self.num_rows);
+        front
+    }
+
     #[inline]
     pub fn merge_block(&mut self, block: DataBlock) {
+        self.domains.take();
         self.columns.reserve(block.num_columns());
         for column in block.columns.into_iter() {
             #[cfg(debug_assertions)]
@@ -342,15 +740,129 @@ impl DataBlock {
         }
     }
 
+    /// Combine two blocks with the same number of rows side by side, like the output of a
+    /// positional join. The columns of `other` follow the columns of `self`, so column
+    /// `i` of `other` ends up at offset `self.num_columns() + i`. The meta of `self` is kept.
+    pub fn zip(mut self, other: DataBlock) -> Result<Self> {
+        if self.num_rows != other.num_rows {
+            return Err(ErrorCode::BadArguments(format!(
+                "cannot zip blocks with different number of rows: {} and {}",
+                self.num_rows, other.num_rows
+            )));
+        }
+        self.merge_block(other);
+        Ok(self)
+    }
+
     #[inline]
     pub fn add_column(&mut self, entry: BlockEntry) {
+        self.domains.take();
         self.columns.push(entry);
         #[cfg(debug_assertions)]
         self.check_valid().unwrap();
     }
 
+    /// Insert a column at `offset`, shifting all columns after it to the right.
+    #[inline]
+    pub fn add_column_at(&mut self, offset: usize, entry: BlockEntry) {
+        assert!(
+            offset <= self.columns.len(),
+            "column offset out of bounds, offset: {}, num_columns: {}",
+            offset,
+            self.columns.len()
+        );
+        self.domains.take();
+        self.columns.insert(offset, entry);
+        #[cfg(debug_assertions)]
+        self.check_valid().unwrap();
+    }
+
+    /// Replace the column at `offset` in place, keeping the order of the other columns.
+    pub fn replace_column(&mut self, offset: usize, entry: BlockEntry) -> Result<()> {
+        if offset >= self.columns.len() {
+            return Err(ErrorCode::Internal(format!(
+                "column offset out of bounds, offset: {}, num_columns: {}",
+                offset,
+                self.columns.len()
+            )));
+        }
+        if let Value::Column(c) = &entry.value {
+            if c.len() != self.num_rows {
+                return Err(ErrorCode::Internal(format!(
+                    "column length mismatch, col: {}, num_rows: {}",
+                    c.len(),
+                    self.num_rows
+                )));
+            }
+        }
+        self.domains.take();
+        self.columns[offset] = entry;
+        Ok(())
+    }
+
+    /// Replace the column at `offset` with the result of `f` applied to it.
+    pub fn map_column(
+        mut self,
+        offset: usize,
+        f: impl FnOnce(&BlockEntry) -> Result<BlockEntry>,
+    ) -> Result<Self> {
+        if offset >= self.columns.len() {
+            return Err(ErrorCode::Internal(format!(
+                "column offset out of bounds, offset: {}, num_columns: {}",
+                offset,
+                self.columns.len()
+            )));
+        }
+        let entry = f(&self.columns[offset])?;
+        self.replace_column(offset, entry)?;
+        Ok(self)
+    }
+
+    /// Finish `builder` and append the built column to the block, checking that its
+    /// length matches the number of rows.
+    pub fn with_builder(mut self, builder: ColumnBuilder) -> Result<Self> {
+        let column = builder.build();
+        if column.len() != self.num_rows {
+            return Err(ErrorCode::Internal(format!(
+                "column length mismatch, col: {}, num_rows: {}",
+                column.len(),
+                self.num_rows
+            )));
+        }
+        self.domains.take();
+        self.columns
+            .push(BlockEntry::new(column.data_type(), Value::Column(column)));
+        Ok(self)
+    }
+
+    /// Move columns to new offsets according to `mapping` (old offset -> new offset).
+    /// Offsets not present in `mapping` keep their position. Panics if two columns
+    /// would end up at the same offset.
+    pub fn remap_columns(self, mapping: &HashMap<usize, usize>) -> Self {
+        let num_columns = self.columns.len();
+        let mut columns: Vec<Option<BlockEntry>> = vec![None; num_columns];
+        for (offset, entry) in self.columns.into_iter().enumerate() {
+            let target = mapping.get(&offset).copied().unwrap_or(offset);
+            assert!(
+                target < num_columns,
+                "column offset out of bounds, offset: {}, num_columns: {}",
+                target,
+                num_columns
+            );
+            assert!(
+                columns[target].is_none(),
+                "duplicate column offset after remapping: {}",
+                target
+            );
+            columns[target] = Some(entry);
+        }
+        let columns = columns.into_iter().map(Option::unwrap).collect();
+        DataBlock::new_with_meta(columns, self.num_rows, self.meta)
+    }
+
     #[inline]
     pub fn pop_columns(&mut self, num: usize) {
+        self.domains.take();
         debug_assert!(num <= self.columns.len());
         self.columns.truncate(self.columns.len() - num);
     }
@@ -382,6 +894,7 @@ impl DataBlock {
             columns,
             num_rows: self.num_rows,
             meta: self.meta,
+            domains: OnceLock::new(),
         })
     }
 
@@ -397,6 +910,7 @@ impl DataBlock {
             columns: self.columns,
             num_rows: self.num_rows,
             meta,
+            domains: self.domains,
         })
     }
 
@@ -415,6 +929,19 @@ impl DataBlock {
         self.meta
     }
 
+    /// Attach a [`PartitionMeta`] to the block, fails if it already has a meta.
+    pub fn with_partition(self, partition_id: usize) -> Result<Self> {
+        self.add_meta(Some(Box::new(PartitionMeta { partition_id })))
+    }
+
+    /// The partition id recorded by [`DataBlock::with_partition`], if any.
+    pub fn partition_id(&self) -> Option<usize> {
+        self.meta
+            .as_ref()
+            .and_then(PartitionMeta::downcast_ref_from)
+            .map(|meta| meta.partition_id)
+    }
+
     pub fn from_arrow_chunk<A: AsRef<dyn Array>>(
         arrow_chunk: &ArrowChunk<A>,
         schema: &DataSchema,
@@ -434,6 +961,67 @@ impl DataBlock {
         Ok(DataBlock::new(cols, arrow_chunk.len()))
     }
 
+    /// Serialize the block into an arrow IPC file buffer, using the field names and
+    /// types of `schema`. The buffer starts with [`IPC_FORMAT_VERSION`] and can be read
+    /// back with [`DataBlock::from_ipc`].
+    pub fn to_ipc(&self, schema: &DataSchema) -> Result<Vec<u8>> {
+        let mut writer = ArrowSpillWriter::try_create(schema.fields())?;
+        writer.write(self)?;
+        let ipc = writer.finish()?;
+
+        let mut bytes = Vec::with_capacity(ipc.len() + 1);
+        bytes.push(IPC_FORMAT_VERSION);
+        bytes.extend_from_slice(&ipc);
+        Ok(bytes)
+    }
+
+    /// Deserialize an arrow IPC file buffer written by [`DataBlock::to_ipc`].
+    ///
+    /// Returns an error if the buffer was written by a newer format version, or if the
+    /// field names or nullability in the file do not match `schema`.
+    pub fn from_ipc(bytes: &[u8], schema: &DataSchema) -> Result<Self> {
+        let bytes = match bytes.first() {
+            // Written without a version prefix.
+            _ if bytes.starts_with(ARROW_IPC_MAGIC) => bytes,
+            Some(&version) if version <= IPC_FORMAT_VERSION => &bytes[1..],
+            Some(&version) => {
+                return Err(ErrorCode::BadBytes(format!(
+                    "unsupported IPC format version {version}, the latest supported version is {IPC_FORMAT_VERSION}"
+                )));
+            }
+            None => return Err(ErrorCode::BadBytes("empty IPC buffer")),
+        };
+        let mut cursor = Cursor::new(bytes);
+        let metadata = read_file_metadata(&mut cursor)?;
+
+        let fields = &metadata.schema.fields;
+        if fields.len() != schema.num_fields() {
+            return Err(ErrorCode::BadBytes(format!(
+                "expected {} fields in arrow IPC file, but got {}",
+                schema.num_fields(),
+                fields.len()
+            )));
+        }
+        for (arrow_field, field) in fields.iter().zip(schema.fields()) {
+            let expected = ArrowField::from(field);
+            if arrow_field.name != expected.name || arrow_field.is_nullable != expected.is_nullable
+            {
+                return Err(ErrorCode::BadBytes(format!(
+                    "arrow IPC field {:?} (nullable: {}) does not match schema field {:?} (nullable: {})",
+                    arrow_field.name, arrow_field.is_nullable, expected.name, expected.is_nullable
+                )));
+            }
+        }
+
+        let blocks = FileReader::new(cursor, metadata, None, None)
+            .map(|chunk| DataBlock::from_arrow_chunk(&chunk?, schema))
+            .collect::<Result<Vec<_>>>()?;
+        if blocks.is_empty() {
+            return Ok(DataBlock::empty_with_schema(Arc::new(schema.clone())));
+        }
+        DataBlock::concat(&blocks)
+    }
+
     // If default_vals[i].is_some(), then DataBlock.column[i] = num_rows * default_vals[i].
     // Else, DataBlock.column[i] = chuck.column.
     // For example, Schema.field is [a,b,c] and default_vals is [Some("a"), None, Some("c")],
@@ -537,6 +1125,7 @@ impl DataBlock {
             columns.push(column);
         }
         self.columns = columns;
+        self.domains.take();
         self
     }
 
@@ -564,16 +1153,33 @@ impl DataBlock {
 impl TryFrom<DataBlock> for ArrowChunk<ArrayRef> {
     type Error = ErrorCode;
 
+    /// Scalar entries are expanded to `num_rows` values, so a block without rows gives
+    /// empty arrays of the column types.
     fn try_from(v: DataBlock) -> Result<ArrowChunk<ArrayRef>> {
+        let num_rows = v.num_rows();
         let arrays = v
-            .convert_to_full()
             .columns()
             .iter()
-            .map(|val| {
-                let column = val.value.clone().into_column().unwrap();
-                column.as_arrow()
+            .enumerate()
+            .map(|(offset, entry)| match &entry.value {
+                Value::Scalar(Scalar::Null) if !entry.data_type.is_nullable_or_null() => {
+                    Err(ErrorCode::BadDataValueType(format!(
+                        "cannot convert column {offset} to arrow, got a NULL scalar for non-nullable type {}",
+                        entry.data_type
+                    )))
+                }
+                Value::Scalar(scalar) => {
+                    Ok(Column::constant(&scalar.as_ref(), num_rows, &entry.data_type).as_arrow())
+                }
+                Value::Column(column) if column.len() != num_rows => {
+                    Err(ErrorCode::BadDataValueType(format!(
+                        "cannot convert column {offset} to arrow, it has {} rows but the block has {num_rows}",
+                        column.len()
+                    )))
+                }
+                Value::Column(column) => Ok(column.as_arrow()),
             })
-            .collect();
+            .collect::<Result<Vec<_>>>()?;
 
         Ok(ArrowChunk::try_new(arrays)?)
     }
@@ -28,6 +28,8 @@ use super::ARROW_EXT_TYPE_VARIANT;
 use crate::types::array::ArrayColumn;
 use crate::types::binary::BinaryColumn;
 use crate::types::decimal::DecimalColumn;
+use crate::types::decimal::MAX_DECIMAL128_PRECISION;
+use crate::types::decimal::MAX_DECIMAL256_PRECISION;
 use crate::types::nullable::NullableColumn;
 use crate::types::string::CheckUTF8;
 use crate::types::string::StringColumn;
@@ -91,20 +93,34 @@ impl TryFrom<&ArrowField> for DataField {
     }
 }
 
+fn arrow_decimal_size(precision: usize, scale: usize, max_precision: u8) -> Result<DecimalSize> {
+    if precision == 0 || precision > max_precision as usize || scale > precision {
+        return Err(ErrorCode::UnknownFormat(format!(
+            "invalid arrow decimal type, precision: {precision}, scale: {scale}, max precision: {max_precision}"
+        )));
+    }
+    Ok(DecimalSize {
+        precision: precision as u8,
+        scale: scale as u8,
+    })
+}
+
 fn arrow_type_to_table_type(ty: &ArrowDataType, is_nullable: bool) -> Result<TableDataType> {
     let ty = with_number_type!(|TYPE| match ty {
         ArrowDataType::TYPE => TableDataType::Number(NumberDataType::TYPE),
 
         ArrowDataType::Decimal(precision, scale) =>
-            TableDataType::Decimal(DecimalDataType::Decimal128(DecimalSize {
-                precision: *precision as u8,
-                scale: *scale as u8,
-            })),
+            TableDataType::Decimal(DecimalDataType::Decimal128(arrow_decimal_size(
+                *precision,
+                *scale,
+                MAX_DECIMAL128_PRECISION
+            )?)),
         ArrowDataType::Decimal256(precision, scale) =>
-            TableDataType::Decimal(DecimalDataType::Decimal256(DecimalSize {
-                precision: *precision as u8,
-                scale: *scale as u8,
-            })),
+            TableDataType::Decimal(DecimalDataType::Decimal256(arrow_decimal_size(
+                *precision,
+                *scale,
+                MAX_DECIMAL256_PRECISION
+            )?)),
 
         ArrowDataType::Null => return Ok(TableDataType::Null),
         ArrowDataType::Boolean => TableDataType::Boolean,
@@ -727,4 +743,22 @@ impl Column {
 
         from_arrow_with_arrow_type(arrow_col, arrow_col.data_type(), data_type)
     }
+
+    /// Convert a top-level arrow array, such as a column of an arrow chunk, into a `Column`.
+    ///
+    /// Unlike [`Column::from_arrow`], an array containing nulls is rejected if `data_type`
+    /// is not nullable, rather than silently dropping its validity. A non-nullable array
+    /// converted to a nullable type gets an all-valid validity.
+    pub fn from_arrow_array(
+        arrow_col: &dyn databend_common_arrow::arrow::array::Array,
+        data_type: &DataType,
+    ) -> Result<Column> {
+        let null_count = arrow_col.validity().map_or(0, |v| v.unset_bits());
+        if null_count > 0 && !data_type.is_nullable_or_null() {
+            return Err(ErrorCode::BadArguments(format!(
+                "cannot convert arrow array with {null_count} nulls to non-nullable type {data_type}"
+            )));
+        }
+        Column::from_arrow(arrow_col, data_type)
+    }
 }
@@ -118,7 +118,7 @@ impl<'a> Evaluator<'a> {
 
         let result = match expr {
             Expr::Constant { scalar, .. } => Ok(Value::Scalar(scalar.clone())),
-            Expr::ColumnRef { id, .. } => Ok(self.data_block.get_by_offset(*id).value.clone()),
+            Expr::ColumnRef { id, .. } => Ok(self.data_block.try_get_by_offset(*id)?.value.clone()),
             Expr::Cast {
                 span,
                 is_try,
@@ -241,7 +241,13 @@ impl<'a> Evaluator<'a> {
                         assert_eq!(
                             ConstantFolder::fold_with_domain(
                                 expr,
-                                &self.data_block.domains().into_iter().enumerate().collect(),
+                                &self
+                                    .data_block
+                                    .domains()
+                                    .iter()
+                                    .cloned()
+                                    .enumerate()
+                                    .collect(),
                                 self.func_ctx,
                                 self.fn_registry
                             )
@@ -1327,7 +1333,13 @@ impl<'a> Evaluator<'a> {
                 assert_eq!(
                     ConstantFolder::fold_with_domain(
                         expr,
-                        &self.data_block.domains().into_iter().enumerate().collect(),
+                        &self
+                            .data_block
+                            .domains()
+                            .iter()
+                            .cloned()
+                            .enumerate()
+                            .collect(),
                         self.func_ctx,
                         self.fn_registry
                     )
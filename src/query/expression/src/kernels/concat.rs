@@ -76,8 +76,7 @@ impl DataBlock {
                     let entry = &block.get_by_offset(i);
                     match &entry.value {
                         Value::Scalar(s) => {
-                            ColumnBuilder::repeat(&s.as_ref(), block.num_rows(), &entry.data_type)
-                                .build()
+                            Column::constant(&s.as_ref(), block.num_rows(), &entry.data_type)
                         }
                         Value::Column(c) => c.clone(),
                     }
@@ -96,6 +95,36 @@ impl DataBlock {
 }
 
 impl Column {
+    /// Concatenate columns of the same data type. Nullable and non-nullable columns of the
+    /// same inner type are unified to a nullable column; any other mismatch is rejected.
+    pub fn concat(columns: &[Column]) -> Result<Column> {
+        let Some(first) = columns.first() else {
+            return Err(ErrorCode::EmptyData("Can't concat empty columns"));
+        };
+        let data_type = first.data_type();
+        let inner_type = data_type.remove_nullable();
+        let mut same_type = true;
+        for column in columns {
+            let column_type = column.data_type();
+            if column_type.remove_nullable() != inner_type {
+                return Err(ErrorCode::BadArguments(format!(
+                    "Can't concat columns of different types, {} and {}",
+                    data_type, column_type
+                )));
+            }
+            same_type &= column_type == data_type;
+        }
+
+        if same_type {
+            return Self::concat_columns(columns.iter().cloned());
+        }
+        let columns = columns
+            .iter()
+            .map(|c| c.clone().wrap_nullable(None))
+            .collect::<Vec<_>>();
+        Self::concat_columns(columns.into_iter())
+    }
+
     pub fn concat_columns<I: Iterator<Item = Column> + TrustedLen + Clone>(
         columns: I,
     ) -> Result<Column> {
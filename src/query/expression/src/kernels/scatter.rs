@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use itertools::Itertools;
 
@@ -20,8 +21,28 @@ use crate::kernels::utils::store_advance_aligned;
 use crate::DataBlock;
 
 impl DataBlock {
+    /// Partition the rows into `scatter_size` blocks, row `i` goes to the block
+    /// `indices[i]`. Rows keep their relative order within each block.
+    ///
+    /// Returns an error if `indices` does not have one entry per row or names a
+    /// block out of `0..scatter_size`.
     pub fn scatter<I>(&self, indices: &[I], scatter_size: usize) -> Result<Vec<Self>>
     where I: databend_common_arrow::arrow::types::Index {
+        if indices.len() != self.num_rows() {
+            return Err(ErrorCode::BadArguments(format!(
+                "scatter indices length {} does not match the number of rows {}",
+                indices.len(),
+                self.num_rows()
+            )));
+        }
+        if let Some(index) = indices.iter().find(|i| i.to_usize() >= scatter_size) {
+            return Err(ErrorCode::BadArguments(format!(
+                "scatter index {} out of scatter size {}",
+                index.to_usize(),
+                scatter_size
+            )));
+        }
+
         if indices.is_empty() {
             let mut result = Vec::with_capacity(scatter_size);
             result.push(self.clone());
@@ -100,6 +100,53 @@ impl DataBlock {
         DataBlock::take(block, indices.values(), &mut None)
     }
 
+    /// Return the first `n` rows of `block` in the order of `descriptions`, like
+    /// `DataBlock::sort` followed by `slice(0..n)`. Only the selected rows are fully
+    /// sorted, and rows with equal sort keys keep their original order.
+    pub fn top_n(
+        block: &DataBlock,
+        descriptions: &[SortColumnDescription],
+        n: usize,
+    ) -> Result<DataBlock> {
+        let num_rows = block.num_rows();
+        let n = n.min(num_rows);
+        if n == 0 {
+            return Ok(block.slice(0..0));
+        }
+
+        let order_columns = descriptions
+            .iter()
+            .map(|d| column_to_arrow_array(block.get_by_offset(d.offset), num_rows))
+            .collect::<Vec<_>>();
+        let order_arrays = order_columns
+            .iter()
+            .map(|array| vec![array.as_ref()])
+            .collect::<Vec<_>>();
+        let sort_options = descriptions
+            .iter()
+            .map(|d| arrow_sort::SortOptions {
+                descending: !d.asc,
+                nulls_first: d.nulls_first,
+            })
+            .collect::<Vec<_>>();
+        let sort_options_with_arrays = order_arrays
+            .iter()
+            .zip(sort_options.iter())
+            .map(|(arrays, opt)| (arrays as &[&dyn Array], opt))
+            .collect::<Vec<_>>();
+        let comparator = build_comparator_impl(&sort_options_with_arrays, &build_compare)?;
+        let compare =
+            |a: &u32, b: &u32| comparator(0, *a as usize, 0, *b as usize).then_with(|| a.cmp(b));
+
+        let mut indices = (0..num_rows as u32).collect::<Vec<_>>();
+        if n < num_rows {
+            indices.select_nth_unstable_by(n - 1, compare);
+            indices.truncate(n);
+        }
+        indices.sort_unstable_by(compare);
+        DataBlock::take(block, &indices, &mut None)
+    }
+
     // merge two blocks to one sorted block
     // require: lhs and rhs have been `convert_to_full`.
     fn two_way_merge_sort(
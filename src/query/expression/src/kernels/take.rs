@@ -16,6 +16,7 @@ use std::sync::Arc;
 
 use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_arrow::arrow::buffer::Buffer;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 
 use crate::kernels::utils::copy_advance_aligned;
@@ -82,9 +83,28 @@ impl DataBlock {
             self.get_meta().cloned(),
         ))
     }
+
+    /// Reverse the row order. Scalar entries and the meta are kept as they are.
+    pub fn reverse(&self) -> Result<Self> {
+        let indices = (0..self.num_rows() as u32).rev().collect::<Vec<_>>();
+        self.take(&indices, &mut None)
+    }
 }
 
 impl Column {
+    /// Take the rows at `indices`, returning an error instead of panicking
+    /// if an index is out of bounds.
+    pub fn checked_take(&self, indices: &[u32]) -> Result<Self> {
+        if let Some(index) = indices.iter().find(|i| **i as usize >= self.len()) {
+            return Err(ErrorCode::BadArguments(format!(
+                "take index {} is out of bounds for a column of length {}",
+                index,
+                self.len()
+            )));
+        }
+        Ok(self.take(indices, &mut None))
+    }
+
     pub fn take<I>(&self, indices: &[I], string_items_buf: &mut Option<Vec<(u64, usize)>>) -> Self
     where I: databend_common_arrow::arrow::types::Index {
         match self {
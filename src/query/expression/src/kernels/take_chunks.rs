@@ -100,11 +100,7 @@ impl DataBlock {
                 let full_columns: Vec<Column> = columns
                     .iter()
                     .map(|(entry, rows)| match &entry.value {
-                        Value::Scalar(s) => {
-                            let builder =
-                                ColumnBuilder::repeat(&s.as_ref(), *rows, &entry.data_type);
-                            builder.build()
-                        }
+                        Value::Scalar(s) => Column::constant(&s.as_ref(), *rows, &entry.data_type),
                         Value::Column(c) => c.clone(),
                     })
                     .collect();
@@ -207,8 +203,8 @@ impl DataBlock {
             let col = &columns[*index];
             match &col.value {
                 Value::Scalar(scalar) => {
-                    let other = ColumnBuilder::repeat(&scalar.as_ref(), len, &col.data_type);
-                    builder.append_column(&other.build());
+                    let other = Column::constant(&scalar.as_ref(), len, &col.data_type);
+                    builder.append_column(&other);
                 }
                 Value::Column(c) => {
                     let c = c.slice(*start..(*start + len));
@@ -17,6 +17,7 @@ use std::sync::Arc;
 
 use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_arrow::arrow::buffer::Buffer;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 
 use crate::kernels::take::BIT_MASK;
@@ -67,6 +68,37 @@ impl DataBlock {
             .collect();
         Ok(DataBlock::new(columns, num_rows))
     }
+
+    /// Gather the rows of several contiguous ranges in one pass.
+    ///
+    /// The ranges must be sorted, non-overlapping and within the block. This is cheaper
+    /// than a bitmap filter when the selection is made of a few long runs.
+    pub fn take_sorted_ranges(&self, ranges: &[Range<usize>]) -> Result<DataBlock> {
+        let mut prev_end = 0;
+        for range in ranges {
+            if range.start < prev_end || range.start > range.end {
+                return Err(ErrorCode::BadArguments(format!(
+                    "ranges must be sorted and non-overlapping, got {:?} after end {}",
+                    range, prev_end
+                )));
+            }
+            prev_end = range.end;
+        }
+        if prev_end > self.num_rows() {
+            return Err(ErrorCode::BadArguments(format!(
+                "range end {} is out of bounds for a block of {} rows",
+                prev_end,
+                self.num_rows()
+            )));
+        }
+
+        let num_rows = ranges.iter().map(|range| range.len()).sum();
+        let ranges = ranges
+            .iter()
+            .map(|range| range.start as u32..range.end as u32)
+            .collect::<Vec<_>>();
+        self.clone().take_ranges(&ranges, num_rows)
+    }
 }
 
 impl Column {
@@ -334,6 +334,45 @@ impl Domain {
         }
     }
 
+    /// Return the `(min, max)` bounds of the domain.
+    ///
+    /// `Scalar::Null` is returned for a bound that is unknown, e.g. the domain
+    /// is `Undefined` or only contains NULL.
+    pub fn to_minmax(&self) -> (Scalar, Scalar) {
+        match self {
+            Domain::Number(domain) => with_number_type!(|TYPE| match domain {
+                NumberDomain::TYPE(SimpleDomain { min, max }) => (
+                    Scalar::Number(NumberScalar::TYPE(*min)),
+                    Scalar::Number(NumberScalar::TYPE(*max)),
+                ),
+            }),
+            Domain::Decimal(domain) => with_decimal_type!(|TYPE| match domain {
+                DecimalDomain::TYPE(SimpleDomain { min, max }, size) => (
+                    Scalar::Decimal(DecimalScalar::TYPE(*min, *size)),
+                    Scalar::Decimal(DecimalScalar::TYPE(*max, *size)),
+                ),
+            }),
+            Domain::Boolean(BooleanDomain {
+                has_false,
+                has_true,
+            }) if *has_false || *has_true => {
+                (Scalar::Boolean(!has_false), Scalar::Boolean(*has_true))
+            }
+            Domain::String(StringDomain { min, max }) => (
+                Scalar::String(min.clone()),
+                max.clone().map(Scalar::String).unwrap_or(Scalar::Null),
+            ),
+            Domain::Timestamp(SimpleDomain { min, max }) => {
+                (Scalar::Timestamp(*min), Scalar::Timestamp(*max))
+            }
+            Domain::Date(SimpleDomain { min, max }) => (Scalar::Date(*min), Scalar::Date(*max)),
+            Domain::Nullable(NullableDomain {
+                value: Some(value), ..
+            }) => value.to_minmax(),
+            _ => (Scalar::Null, Scalar::Null),
+        }
+    }
+
     pub fn as_singleton(&self) -> Option<Scalar> {
         match self {
             Domain::Number(NumberDomain::Int8(SimpleDomain { min, max })) if min == max => {
@@ -248,8 +248,10 @@ impl<T: ValueType> ArrayColumn<T> {
         }
     }
 
+    /// Only the values referenced by `offsets` are counted, so a sliced column
+    /// does not report the values it shares with other slices.
     pub fn memory_size(&self) -> usize {
-        T::column_memory_size(&self.values) + self.offsets.len() * 8
+        T::column_memory_size(&self.underlying_column()) + self.offsets.len() * 8
     }
 
     pub fn underlying_column(&self) -> T::Column {
@@ -600,6 +600,10 @@ impl NumberColumn {
         })
     }
 
+    /// Floats are compared as `OrderedFloat`, the same total order SQL comparisons use,
+    /// where NaN is greater than every other value. So a NaN is only the min of an all-NaN
+    /// column, and it is the max as soon as the column contains one, which keeps the NaN
+    /// rows inside the domain when it is used for pruning.
     pub fn domain(&self) -> NumberDomain {
         assert!(self.len() > 0);
         crate::with_number_type!(|NUM_TYPE| match self {
@@ -18,17 +18,25 @@ use databend_common_arrow::arrow::array::Array;
 use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_arrow::arrow::bitmap::MutableBitmap;
 use databend_common_arrow::arrow::buffer::Buffer;
+use databend_common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use databend_common_arrow::arrow::datatypes::Field as ArrowField;
 use databend_common_arrow::arrow::datatypes::Schema;
+use databend_common_arrow::arrow::io::flight::default_ipc_fields;
+use databend_common_arrow::arrow::io::flight::serialize_batch;
+use databend_common_arrow::arrow::io::flight::serialize_schema;
+use databend_common_arrow::arrow::io::flight::WriteOptions as FlightWriteOptions;
 use databend_common_arrow::arrow::io::ipc::read::read_file_metadata;
 use databend_common_arrow::arrow::io::ipc::read::FileReader;
 use databend_common_arrow::arrow::io::ipc::write::FileWriter;
 use databend_common_arrow::arrow::io::ipc::write::WriteOptions as IpcWriteOptions;
+use databend_common_arrow::arrow_format::flight::data::FlightData;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 
 use crate::BlockEntry;
 use crate::Column;
 use crate::ColumnBuilder;
+use crate::DataBlock;
 use crate::DataField;
 use crate::Value;
 
@@ -81,48 +89,197 @@ pub fn serialize_column(col: &Column) -> Vec<u8> {
     buffer
 }
 
+/// Deserialize an arrow IPC file written with a single column.
+///
+/// Returns an error if the file contains more than one column.
 pub fn deserialize_column(bytes: &[u8]) -> Result<Column> {
+    let mut columns = deserialize_columns(bytes)?;
+    if columns.len() != 1 {
+        return Err(ErrorCode::Internal(format!(
+            "expected one arrow column, but got {}",
+            columns.len()
+        )));
+    }
+    Ok(columns.remove(0))
+}
+
+/// Deserialize all columns of an arrow IPC file, concatenating the record batches.
+pub fn deserialize_columns(bytes: &[u8]) -> Result<Vec<Column>> {
     let mut cursor = Cursor::new(bytes);
 
     let metadata = read_file_metadata(&mut cursor)?;
-    let f = metadata.schema.fields[0].clone();
-    let data_field = DataField::try_from(&f)?;
+    let data_fields = metadata
+        .schema
+        .fields
+        .iter()
+        .map(DataField::try_from)
+        .collect::<Result<Vec<_>>>()?;
 
-    let mut reader = FileReader::new(cursor, metadata, None, None);
-    let col = reader
-        .next()
-        .ok_or_else(|| ErrorCode::Internal("expected one arrow array"))??
-        .into_arrays()
-        .remove(0);
+    let mut batches = vec![Vec::new(); data_fields.len()];
+    let reader = FileReader::new(cursor, metadata, None, None);
+    for chunk in reader {
+        for ((array, field), columns) in chunk?
+            .into_arrays()
+            .iter()
+            .zip(data_fields.iter())
+            .zip(batches.iter_mut())
+        {
+            columns.push(Column::from_arrow(array.as_ref(), field.data_type())?);
+        }
+    }
 
-    Column::from_arrow(col.as_ref(), data_field.data_type())
+    batches
+        .into_iter()
+        .zip(data_fields.iter())
+        .map(|(columns, field)| match columns.len() {
+            0 => Ok(ColumnBuilder::with_capacity(field.data_type(), 0).build()),
+            1 => Ok(columns.into_iter().next().unwrap()),
+            _ => Column::concat_columns(columns.into_iter()),
+        })
+        .collect()
+}
+
+/// Encode a block as arrow Flight messages: the schema message first, then the
+/// dictionary batches (if any), and finally the record batch.
+pub fn serialize_flight_data(
+    block: DataBlock,
+    fields: &[DataField],
+    options: &FlightWriteOptions,
+) -> Result<Vec<FlightData>> {
+    if block.num_columns() != fields.len() {
+        return Err(ErrorCode::Internal(format!(
+            "expected {} columns to serialize, but got {}",
+            fields.len(),
+            block.num_columns()
+        )));
+    }
+
+    let schema = Schema::from(fields.iter().map(ArrowField::from).collect::<Vec<_>>());
+    let ipc_fields = default_ipc_fields(&schema.fields);
+
+    let chunk: ArrowChunk<Box<dyn Array>> = block.try_into()?;
+    let (dictionaries, batch) = serialize_batch(&chunk, &ipc_fields, options)?;
+
+    let mut messages = Vec::with_capacity(dictionaries.len() + 2);
+    messages.push(serialize_schema(&schema, Some(&ipc_fields)));
+    messages.extend(dictionaries);
+    messages.push(batch);
+    Ok(messages)
+}
+
+/// Writes multiple blocks sharing one schema into a single arrow IPC file.
+///
+/// Unlike calling [`serialize_column`] per column, the schema header is only emitted once,
+/// and the encoding scratch buffers are reused across all written blocks.
+pub struct ArrowSpillWriter {
+    writer: FileWriter<Vec<u8>>,
+    num_columns: usize,
+}
+
+impl ArrowSpillWriter {
+    pub fn try_create(fields: &[DataField]) -> Result<Self> {
+        let schema = Schema::from(fields.iter().map(ArrowField::from).collect::<Vec<_>>());
+        let mut writer = FileWriter::new(Vec::new(), schema, None, IpcWriteOptions::default());
+        writer.start()?;
+
+        Ok(ArrowSpillWriter {
+            writer,
+            num_columns: fields.len(),
+        })
+    }
+
+    pub fn write(&mut self, block: &DataBlock) -> Result<()> {
+        if block.num_columns() != self.num_columns {
+            return Err(ErrorCode::Internal(format!(
+                "ArrowSpillWriter expects {} columns, but got {}",
+                self.num_columns,
+                block.num_columns()
+            )));
+        }
+
+        let arrays = block
+            .columns()
+            .iter()
+            .map(|entry| column_to_arrow_array(entry, block.num_rows()))
+            .collect::<Vec<_>>();
+        self.writer.write(&ArrowChunk::try_new(arrays)?, None)?;
+        Ok(())
+    }
+
+    /// Write the IPC footer and return the whole file buffer.
+    pub fn finish(mut self) -> Result<Vec<u8>> {
+        self.writer.finish()?;
+        Ok(self.writer.into_inner())
+    }
 }
 
 /// Convert a column to a arrow array.
 pub fn column_to_arrow_array(column: &BlockEntry, num_rows: usize) -> Box<dyn Array> {
     match &column.value {
-        Value::Scalar(v) => {
-            let builder = ColumnBuilder::repeat(&v.as_ref(), num_rows, &column.data_type);
-            builder.build().as_arrow()
-        }
+        Value::Scalar(v) => Column::constant(&v.as_ref(), num_rows, &column.data_type).as_arrow(),
         Value::Column(c) => c.as_arrow(),
     }
 }
 
+/// Broadcast a single-bit validity (derived from a scalar) to the length of the other side.
+///
+/// # Panics
+///
+/// Panics if the lengths differ and neither side has length 1.
+fn broadcast_validities(lhs: Bitmap, rhs: Bitmap) -> (Bitmap, Bitmap) {
+    match (lhs.len(), rhs.len()) {
+        (l, r) if l == r => (lhs, rhs),
+        (1, r) => (Bitmap::new_constant(lhs.get_bit(0), r), rhs),
+        (l, 1) => (lhs, Bitmap::new_constant(rhs.get_bit(0), l)),
+        (l, r) => panic!("validity length mismatch, lhs: {}, rhs: {}", l, r),
+    }
+}
+
+/// Combine two validities so that a row is valid only if it is valid on both sides.
+///
+/// A validity of length 1 is broadcast to the length of the other one.
+///
+/// # Panics
+///
+/// Panics if the lengths differ and neither side has length 1.
 pub fn and_validities(lhs: Option<Bitmap>, rhs: Option<Bitmap>) -> Option<Bitmap> {
     match (lhs, rhs) {
         (Some(lhs), None) => Some(lhs),
         (None, Some(rhs)) => Some(rhs),
         (None, None) => None,
-        (Some(lhs), Some(rhs)) => Some((&lhs) & (&rhs)),
+        (Some(lhs), Some(rhs)) => {
+            let (lhs, rhs) = broadcast_validities(lhs, rhs);
+            Some((&lhs) & (&rhs))
+        }
     }
 }
 
+/// Combine two validities so that a row is valid if it is valid on either side.
+///
+/// A validity of length 1 is broadcast to the length of the other one.
+///
+/// # Panics
+///
+/// Panics if the lengths differ and neither side has length 1.
 pub fn or_validities(lhs: Option<Bitmap>, rhs: Option<Bitmap>) -> Option<Bitmap> {
     match (lhs, rhs) {
         (Some(lhs), None) => Some(lhs),
         (None, Some(rhs)) => Some(rhs),
         (None, None) => None,
-        (Some(lhs), Some(rhs)) => Some((&lhs) | (&rhs)),
+        (Some(lhs), Some(rhs)) => {
+            let (lhs, rhs) = broadcast_validities(lhs, rhs);
+            Some((&lhs) | (&rhs))
+        }
     }
 }
+
+/// Convert a column to a arrow array, together with an arrow field named `name`
+/// whose nullability follows the column's data type.
+pub fn column_to_arrow_array_with_field(
+    column: &BlockEntry,
+    name: &str,
+    num_rows: usize,
+) -> (Box<dyn Array>, ArrowField) {
+    let field = ArrowField::from(&DataField::new(name, column.data_type.clone()));
+    (column_to_arrow_array(column, num_rows), field)
+}
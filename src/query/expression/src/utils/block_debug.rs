@@ -60,6 +60,17 @@ pub fn assert_block_value_eq(a: &DataBlock, b: &DataBlock) {
     }
 }
 
+/// Assert two blocks hold the same data, see [`DataBlock::equals_with_epsilon`].
+/// Both blocks are printed if they differ.
+pub fn assert_block_eq(expect: &DataBlock, actual: &DataBlock, epsilon: f64) {
+    assert!(
+        expect.equals_with_epsilon(actual, epsilon),
+        "blocks are not equal\n\nexpected:\n{}\nactual:\n{}",
+        pretty_format_blocks(&[expect.clone()]).unwrap(),
+        pretty_format_blocks(&[actual.clone()]).unwrap(),
+    );
+}
+
 /// Assert with order insensitive.
 /// ['a', 'b'] equals ['b', 'a']
 pub fn assert_blocks_sorted_eq_with_name(test_name: &str, expect: Vec<&str>, blocks: &[DataBlock]) {
@@ -27,6 +27,7 @@ use roaring::RoaringTreemap;
 use rust_decimal::Decimal;
 use rust_decimal::RoundingStrategy;
 
+use crate::block::BlockEntry;
 use crate::block::DataBlock;
 use crate::expression::Expr;
 use crate::expression::RawExpr;
@@ -66,6 +67,8 @@ use crate::FunctionEval;
 use crate::TableDataType;
 
 const FLOAT_NUM_FRAC_DIGITS: u32 = 10;
+/// Number of leading values shown when displaying a column entry.
+const BLOCK_ENTRY_DISPLAY_VALUES: usize = 5;
 
 impl Debug for DataBlock {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -106,6 +109,23 @@ impl Display for DataBlock {
     }
 }
 
+impl Display for BlockEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.value {
+            Value::Scalar(scalar) => write!(f, "{} = Scalar({})", self.data_type, scalar),
+            Value::Column(column) => {
+                write!(f, "{} = Column[{}]: [", self.data_type, column.len())?;
+                let values = column.iter().take(BLOCK_ENTRY_DISPLAY_VALUES).join(", ");
+                write!(f, "{values}")?;
+                if column.len() > BLOCK_ENTRY_DISPLAY_VALUES {
+                    write!(f, ", ...")?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
 impl<'a> Debug for ScalarRef<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -299,10 +299,7 @@ impl<T: Decimal> Value<DecimalType<T>> {
 impl Value<AnyType> {
     pub fn convert_to_full_column(&self, ty: &DataType, num_rows: usize) -> Column {
         match self {
-            Value::Scalar(s) => {
-                let builder = ColumnBuilder::repeat(&s.as_ref(), num_rows, ty);
-                builder.build()
-            }
+            Value::Scalar(s) => Column::constant(&s.as_ref(), num_rows, ty),
             Value::Column(c) => c.clone(),
         }
     }
@@ -899,6 +896,23 @@ impl Column {
         }
     }
 
+    /// Like [`Column::slice`], but returns an error instead of panicking if `range`
+    /// is reversed or out of bounds.
+    ///
+    /// The slice is zero-copy: validity bitmaps are sliced with the same offset as the
+    /// values, and string, binary and array columns share their data buffer while their
+    /// offsets keep pointing into it, so the first offset of a slice is not always zero.
+    pub fn try_slice(&self, range: Range<usize>) -> Result<Self> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(ErrorCode::BadArguments(format!(
+                "slice range {:?} out of column length {}",
+                range,
+                self.len()
+            )));
+        }
+        Ok(self.slice(range))
+    }
+
     pub fn iter(&self) -> ColumnIterator {
         ColumnIterator {
             column: self,
@@ -1051,6 +1065,13 @@ impl Column {
         }
     }
 
+    /// Build a column of `len` rows that all hold `scalar`.
+    ///
+    /// A non-NULL scalar with a nullable `data_type` gives a nullable column with all rows valid.
+    pub fn constant(scalar: &ScalarRef, len: usize, data_type: &DataType) -> Self {
+        ColumnBuilder::repeat(scalar, len, data_type).build()
+    }
+
     pub fn random(ty: &DataType, len: usize) -> Self {
         use rand::distributions::Alphanumeric;
         use rand::rngs::SmallRng;
@@ -1242,8 +1263,8 @@ impl Column {
             Column::String(col) => col.memory_size(),
             Column::Timestamp(col) => col.len() * 8,
             Column::Date(col) => col.len() * 4,
-            Column::Array(col) => col.values.memory_size() + col.offsets.len() * 8,
-            Column::Map(col) => col.values.memory_size() + col.offsets.len() * 8,
+            Column::Array(col) => col.memory_size(),
+            Column::Map(col) => col.memory_size(),
             Column::Bitmap(col) => col.memory_size(),
             Column::Nullable(c) => c.column.memory_size() + c.validity.as_slice().0.len(),
             Column::Tuple(fields) => fields.iter().map(|f| f.memory_size()).sum(),
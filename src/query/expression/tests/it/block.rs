@@ -1,12 +1,37 @@
+use std::collections::HashMap;
+
+use databend_common_arrow::arrow::bitmap::Bitmap;
+use databend_common_arrow::arrow::chunk::Chunk as ArrowChunk;
+use databend_common_arrow::arrow::datatypes::DataType as ArrowDataType;
+use databend_common_arrow::ArrayRef;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::arrow::and_validities;
+use databend_common_expression::arrow::or_validities;
+use databend_common_expression::block_debug::assert_block_eq;
 use databend_common_expression::block_debug::box_render;
 use databend_common_expression::types::string::StringColumnBuilder;
 use databend_common_expression::types::DataType;
+use databend_common_expression::types::Float64Type;
 use databend_common_expression::types::Int32Type;
+use databend_common_expression::types::Int64Type;
 use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::NumberScalar;
+use databend_common_expression::types::StringType;
+use databend_common_expression::BlockEntry;
+use databend_common_expression::BlockMetaInfoDowncast;
 use databend_common_expression::Column;
+use databend_common_expression::ColumnBuilder;
+use databend_common_expression::DataBlock;
 use databend_common_expression::DataField;
 use databend_common_expression::DataSchemaRefExt;
 use databend_common_expression::FromData;
+use databend_common_expression::HashFunction;
+use databend_common_expression::PartitionMeta;
+use databend_common_expression::Scalar;
+use databend_common_expression::ScalarRef;
+use databend_common_expression::Value;
+use databend_common_expression::IPC_FORMAT_VERSION;
 
 use crate::common::new_block;
 
@@ -56,3 +81,839 @@ fn test_box_render_block() {
 └────────────────────┘"#;
     assert_eq!(d, expected);
 }
+
+#[test]
+fn test_try_new_from_columns() {
+    let block = DataBlock::try_new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ])
+    .unwrap();
+    assert_eq!(block.num_rows(), 3);
+    assert_eq!(block.num_columns(), 2);
+    assert_eq!(block.get_by_offset(1).data_type, DataType::String);
+
+    let result = DataBlock::try_new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b"]),
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_check_valid() {
+    let mut block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int32Type::from_data(vec![4, 5, 6]),
+    ]);
+    assert!(block.check_valid().is_ok());
+
+    let col = Int32Type::from_data(vec![7, 8]);
+    block.columns_mut()[1] = BlockEntry::new(col.data_type(), Value::Column(col));
+    let err = block.check_valid().unwrap_err();
+    assert!(err.message().contains("offset: 1, col: 2, num_rows: 3"));
+}
+
+#[test]
+fn test_add_column_at() {
+    let mut block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int32Type::from_data(vec![4, 5, 6]),
+    ]);
+
+    let entry = |col: Column| BlockEntry::new(col.data_type(), Value::Column(col));
+
+    // front
+    block.add_column_at(0, entry(Int64Type::from_data(vec![7, 8, 9])));
+    // middle
+    block.add_column_at(2, entry(StringType::from_data(vec!["a", "b", "c"])));
+    // end
+    block.add_column_at(4, entry(Int64Type::from_data(vec![10, 11, 12])));
+
+    assert_eq!(block.num_columns(), 5);
+    assert_eq!(block.num_rows(), 3);
+    let columns = block
+        .columns()
+        .iter()
+        .map(|entry| entry.value.as_column().unwrap().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(columns, vec![
+        Int64Type::from_data(vec![7, 8, 9]),
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+        Int32Type::from_data(vec![4, 5, 6]),
+        Int64Type::from_data(vec![10, 11, 12]),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "column offset out of bounds")]
+fn test_add_column_at_out_of_bounds() {
+    let mut block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    let col = Int32Type::from_data(vec![4, 5, 6]);
+    block.add_column_at(2, BlockEntry::new(col.data_type(), Value::Column(col)));
+}
+
+#[test]
+fn test_domains_cache_invalidation() {
+    let int32 = |v: i32| Scalar::Number(NumberScalar::Int32(v));
+    let entry = |col: Column| BlockEntry::new(col.data_type(), Value::Column(col));
+
+    let mut block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    assert_eq!(block.domains()[0].to_minmax(), (int32(1), int32(3)));
+    // The second call reuses the cached domains instead of scanning the columns again.
+    assert!(std::ptr::eq(block.domains(), block.domains()));
+
+    block.add_column(entry(Int32Type::from_data(vec![7, 8, 9])));
+    let domains = block.domains();
+    assert_eq!(domains.len(), 2);
+    assert_eq!(domains[1].to_minmax(), (int32(7), int32(9)));
+
+    block.columns_mut()[0] = entry(Int32Type::from_data(vec![10, 20, 30]));
+    assert_eq!(block.domains()[0].to_minmax(), (int32(10), int32(30)));
+
+    let sliced = block.slice(0..1);
+    assert_eq!(sliced.domains()[0].to_minmax(), (int32(10), int32(10)));
+    assert_eq!(sliced.domains()[1].to_minmax(), (int32(7), int32(7)));
+}
+
+#[test]
+fn test_validity_mask() {
+    let mut block = new_block(&[
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3), None]),
+        Int32Type::from_opt_data(vec![Some(1), Some(2), None, None]),
+    ]);
+
+    let to_vec = |bitmap: Bitmap| bitmap.iter().collect::<Vec<_>>();
+    assert_eq!(to_vec(block.validity_mask(true).unwrap()), vec![
+        true, false, false, false
+    ]);
+    assert_eq!(to_vec(block.validity_mask(false).unwrap()), vec![
+        true, true, true, false
+    ]);
+
+    // a column without nulls makes every row valid when any column is enough
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int32),
+        Value::Column(Int32Type::from_data(vec![1, 2, 3, 4])),
+    ));
+    assert!(block.validity_mask(false).is_none());
+    assert_eq!(to_vec(block.validity_mask(true).unwrap()), vec![
+        true, false, false, false
+    ]);
+
+    // a NULL scalar propagates to every row
+    block.add_column(BlockEntry::new(DataType::Null, Value::Scalar(Scalar::Null)));
+    assert_eq!(to_vec(block.validity_mask(true).unwrap()), vec![false; 4]);
+
+    let block = new_block(&[Int32Type::from_data(vec![1, 2])]);
+    assert!(block.validity_mask(true).is_none());
+}
+
+#[test]
+fn test_replace_column() {
+    let mut block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int32Type::from_data(vec![4, 5, 6]),
+        Int32Type::from_data(vec![7, 8, 9]),
+    ]);
+
+    let col = StringType::from_data(vec!["a", "b", "c"]);
+    block
+        .replace_column(
+            1,
+            BlockEntry::new(DataType::String, Value::Column(col.clone())),
+        )
+        .unwrap();
+    assert_eq!(block.num_rows(), 3);
+    assert_eq!(block.num_columns(), 3);
+    assert_eq!(
+        block.get_by_offset(0).value,
+        Value::Column(Int32Type::from_data(vec![1, 2, 3]))
+    );
+    assert_eq!(block.get_by_offset(1).value, Value::Column(col));
+    assert_eq!(
+        block.get_by_offset(2).value,
+        Value::Column(Int32Type::from_data(vec![7, 8, 9]))
+    );
+
+    let col = Int32Type::from_data(vec![1, 2]);
+    assert!(
+        block
+            .replace_column(0, BlockEntry::new(col.data_type(), Value::Column(col)))
+            .is_err()
+    );
+    assert!(
+        block
+            .replace_column(
+                3,
+                BlockEntry::new(DataType::Null, Value::Scalar(Scalar::Null))
+            )
+            .is_err()
+    );
+}
+
+#[test]
+fn test_hash_columns() {
+    let block = new_block(&[
+        Int32Type::from_opt_data(vec![Some(1), Some(1), None, None, Some(2)]),
+        StringType::from_data(vec!["a", "a", "a", "a", "a"]),
+        Int32Type::from_data(vec![1, 2, 3, 4, 5]),
+    ]);
+
+    let hashes = block.hash_columns(&[0, 1], HashFunction::Fast);
+    assert_eq!(hashes.len(), 5);
+    assert_eq!(hashes[0], hashes[1]);
+    assert_eq!(hashes[2], hashes[3]);
+    assert_ne!(hashes[0], hashes[2]);
+    assert_ne!(hashes[0], hashes[4]);
+
+    // The values of the other columns are part of the key.
+    let other_hashes = block.hash_columns(&[0, 2], HashFunction::Fast);
+    assert_ne!(other_hashes[0], other_hashes[1]);
+
+    // Nullable and non-nullable columns, or scalars, hash equal keys equally.
+    let other = DataBlock::new(
+        vec![
+            BlockEntry::new(
+                DataType::Number(NumberDataType::Int32),
+                Value::Column(Int32Type::from_data(vec![1, 2])),
+            ),
+            BlockEntry::new(
+                DataType::String,
+                Value::Scalar(Scalar::String("a".as_bytes().to_vec())),
+            ),
+        ],
+        2,
+    );
+    let other_hashes = other.hash_columns(&[0, 1], HashFunction::Fast);
+    assert_eq!(other_hashes[0], hashes[0]);
+    assert_eq!(other_hashes[1], hashes[4]);
+}
+
+#[test]
+fn test_hash_columns_seeded() {
+    let block = new_block(&[
+        Int32Type::from_opt_data(vec![Some(1), None, Some(2)]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ]);
+
+    let hashes = block.hash_columns(&[0, 1], HashFunction::Seeded(42));
+    assert_eq!(
+        hashes,
+        block.hash_columns(&[0, 1], HashFunction::Seeded(42))
+    );
+    assert_ne!(hashes, block.hash_columns(&[0, 1], HashFunction::Seeded(7)));
+
+    // A seeded hash only depends on the seed and the values, so a block built
+    // separately with the same keys gets the same hashes.
+    let other = new_block(&[
+        Int32Type::from_opt_data(vec![Some(1), None, Some(2)]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ]);
+    assert_eq!(
+        hashes,
+        other.hash_columns(&[0, 1], HashFunction::Seeded(42))
+    );
+}
+
+#[test]
+fn test_remap_columns() {
+    let block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+        Int64Type::from_data(vec![4, 5, 6]),
+    ]);
+
+    // swap the first two columns, leave the last one untouched
+    let mapping = HashMap::from([(0, 1), (1, 0)]);
+    let block = block.remap_columns(&mapping);
+
+    let columns = block
+        .columns()
+        .iter()
+        .map(|entry| entry.value.as_column().unwrap().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(columns, vec![
+        StringType::from_data(vec!["a", "b", "c"]),
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int64Type::from_data(vec![4, 5, 6]),
+    ]);
+}
+
+#[test]
+#[should_panic(expected = "duplicate column offset after remapping")]
+fn test_remap_columns_collision() {
+    let block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int32Type::from_data(vec![4, 5, 6]),
+    ]);
+    // column 0 is moved onto column 1, which stays in place
+    block.remap_columns(&HashMap::from([(0, 1)]));
+}
+
+#[test]
+fn test_convert_to_full_shares_columns() {
+    let int_ptr = |entry: &BlockEntry| {
+        entry
+            .value
+            .as_column()
+            .unwrap()
+            .as_number()
+            .unwrap()
+            .as_int32()
+            .unwrap()
+            .as_ptr()
+    };
+
+    // Block without scalar entries.
+    let block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ]);
+    let full = block.convert_to_full();
+    assert_eq!(full.num_rows(), 3);
+    assert_eq!(
+        int_ptr(full.get_by_offset(0)),
+        int_ptr(block.get_by_offset(0))
+    );
+
+    // Block mixing columns and scalars: only the scalars are densified.
+    let mut block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    block.add_column(BlockEntry::new(
+        DataType::String,
+        Value::Scalar(Scalar::String(b"x".to_vec())),
+    ));
+    let full = block.convert_to_full();
+    assert_eq!(
+        int_ptr(full.get_by_offset(0)),
+        int_ptr(block.get_by_offset(0))
+    );
+    assert_eq!(
+        full.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_data(vec!["x", "x", "x"])
+    );
+}
+
+#[test]
+fn test_column_stats() {
+    let block = new_block(&[
+        Int32Type::from_data(vec![3, 1, 4, 1, 5]),
+        Int32Type::from_opt_data(vec![Some(2), None, Some(7), None, Some(2)]),
+    ]);
+
+    let stats = block.column_stats(0);
+    assert_eq!(stats.min, Scalar::Number(NumberScalar::Int32(1)));
+    assert_eq!(stats.max, Scalar::Number(NumberScalar::Int32(5)));
+    assert_eq!(stats.null_count, 0);
+    assert_eq!(stats.distinct_count, 4);
+
+    let stats = block.column_stats(1);
+    assert_eq!(stats.min, Scalar::Number(NumberScalar::Int32(2)));
+    assert_eq!(stats.max, Scalar::Number(NumberScalar::Int32(7)));
+    assert_eq!(stats.null_count, 2);
+    assert_eq!(stats.distinct_count, 2);
+
+    // The cached domains give the same min/max.
+    block.domains();
+    assert_eq!(block.column_stats(1), stats);
+
+    // All NULLs.
+    let block = new_block(&[Int32Type::from_opt_data(vec![None, None])]);
+    let stats = block.column_stats(0);
+    assert_eq!(stats.min, Scalar::Null);
+    assert_eq!(stats.max, Scalar::Null);
+    assert_eq!(stats.null_count, 2);
+    assert_eq!(stats.distinct_count, 0);
+}
+
+#[test]
+fn test_map_column() {
+    let block = new_block(&[
+        StringType::from_data(vec!["a", "b", "c"]),
+        Int32Type::from_data(vec![1, -2, 3]),
+    ]);
+
+    let negate = |entry: &BlockEntry| {
+        let col = entry.value.as_column().unwrap();
+        let values = col.as_number().unwrap().as_int32().unwrap();
+        let negated = values.iter().map(|v| -v).collect::<Vec<_>>();
+        Ok(BlockEntry::new(
+            entry.data_type.clone(),
+            Value::Column(Int32Type::from_data(negated)),
+        ))
+    };
+    let block = block.map_column(1, negate).unwrap();
+    assert_eq!(block.num_columns(), 2);
+    assert_eq!(
+        block.get_by_offset(0).value,
+        Value::Column(StringType::from_data(vec!["a", "b", "c"]))
+    );
+    assert_eq!(
+        block.get_by_offset(1).value,
+        Value::Column(Int32Type::from_data(vec![-1, 2, -3]))
+    );
+
+    // The mapped column must keep the number of rows.
+    let truncate = |entry: &BlockEntry| {
+        let col = entry.value.as_column().unwrap();
+        Ok(BlockEntry::new(
+            entry.data_type.clone(),
+            Value::Column(col.slice(0..1)),
+        ))
+    };
+    assert!(block.clone().map_column(1, truncate).is_err());
+    assert!(block.map_column(2, negate).is_err());
+}
+
+#[test]
+fn test_with_builder() {
+    let block = new_block(&[StringType::from_data(vec!["a", "b", "c"])]);
+
+    let data_type = DataType::Number(NumberDataType::Int64);
+    let mut builder = ColumnBuilder::with_capacity(&data_type, 3);
+    for i in 0..3 {
+        builder.push(Scalar::Number(NumberScalar::Int64(i * 10)).as_ref());
+    }
+    let block = block.with_builder(builder).unwrap();
+    assert_eq!(block.num_rows(), 3);
+    assert_eq!(block.num_columns(), 2);
+    assert_eq!(block.get_by_offset(1).data_type, data_type);
+    assert_eq!(
+        block.get_by_offset(1).value,
+        Value::Column(Int64Type::from_data(vec![0, 10, 20]))
+    );
+
+    let mut builder = ColumnBuilder::with_capacity(&data_type, 1);
+    builder.push(Scalar::Number(NumberScalar::Int64(1)).as_ref());
+    assert!(block.with_builder(builder).is_err());
+}
+
+#[test]
+fn test_pop_front() {
+    let mut block = new_block(&[Int32Type::from_data((0..10).collect::<Vec<i32>>())]);
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int64),
+        Value::Scalar(Scalar::Number(NumberScalar::Int64(7))),
+    ));
+
+    let mut drained = vec![];
+    for n in [3, 4, 5] {
+        let front = block.pop_front(n);
+        assert_eq!(front.num_columns(), 2);
+        assert_eq!(front.get_by_offset(1).value, block.get_by_offset(1).value);
+        drained.push(front);
+    }
+
+    let rows = drained.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+    assert_eq!(rows, vec![3, 4, 3]);
+    assert_eq!(
+        drained[1].get_by_offset(0).value,
+        Value::Column(Int32Type::from_data(vec![3, 4, 5, 6]))
+    );
+    assert_eq!(
+        drained[2].get_by_offset(0).value,
+        Value::Column(Int32Type::from_data(vec![7, 8, 9]))
+    );
+    assert_eq!(block.num_rows(), 0);
+    assert_eq!(block.num_columns(), 2);
+
+    let front = block.pop_front(1);
+    assert_eq!(front.num_rows(), 0);
+    assert_eq!(block.num_rows(), 0);
+}
+
+#[test]
+fn test_combine_validities() {
+    let lhs = Bitmap::from([true, false, true, false]);
+    let rhs = Bitmap::from([true, true, false, false]);
+    assert_eq!(
+        and_validities(Some(lhs.clone()), Some(rhs.clone())),
+        Some(Bitmap::from([true, false, false, false]))
+    );
+    assert_eq!(
+        or_validities(Some(lhs.clone()), Some(rhs)),
+        Some(Bitmap::from([true, true, true, false]))
+    );
+    assert_eq!(and_validities(Some(lhs.clone()), None), Some(lhs.clone()));
+    assert_eq!(or_validities(None, None), None);
+
+    // A validity derived from a scalar has a single bit and is broadcast.
+    let valid = Bitmap::from([true]);
+    let invalid = Bitmap::from([false]);
+    assert_eq!(
+        and_validities(Some(valid.clone()), Some(lhs.clone())),
+        Some(lhs.clone())
+    );
+    assert_eq!(
+        and_validities(Some(lhs.clone()), Some(invalid.clone())),
+        Some(Bitmap::new_constant(false, 4))
+    );
+    assert_eq!(
+        or_validities(Some(lhs.clone()), Some(valid)),
+        Some(Bitmap::new_constant(true, 4))
+    );
+    assert_eq!(or_validities(Some(invalid), Some(lhs.clone())), Some(lhs));
+}
+
+#[test]
+#[should_panic(expected = "validity length mismatch")]
+fn test_and_validities_length_mismatch() {
+    and_validities(
+        Some(Bitmap::from([true, false, true])),
+        Some(Bitmap::from([true, false])),
+    );
+}
+
+#[test]
+#[should_panic(expected = "validity length mismatch")]
+fn test_or_validities_length_mismatch() {
+    or_validities(
+        Some(Bitmap::from([true, false])),
+        Some(Bitmap::from([true, false, true])),
+    );
+}
+
+#[test]
+fn test_ipc_round_trip() {
+    let block = new_block(&[
+        StringType::from_data(vec!["a", "b", "c"]),
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3)]),
+    ]);
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("name", DataType::String),
+        DataField::new(
+            "value",
+            DataType::Nullable(Box::new(DataType::Number(NumberDataType::Int32))),
+        ),
+    ]);
+
+    let bytes = block.to_ipc(&schema).unwrap();
+    let result = DataBlock::from_ipc(&bytes, &schema).unwrap();
+    assert_eq!(result.num_rows(), 3);
+    assert_eq!(result.columns(), block.columns());
+
+    // Field names and nullability must match the schema.
+    let renamed = DataSchemaRefExt::create(vec![
+        DataField::new("other", DataType::String),
+        schema.field(1).clone(),
+    ]);
+    assert!(DataBlock::from_ipc(&bytes, &renamed).is_err());
+    let non_nullable = DataSchemaRefExt::create(vec![
+        schema.field(0).clone(),
+        DataField::new("value", DataType::Number(NumberDataType::Int32)),
+    ]);
+    assert!(DataBlock::from_ipc(&bytes, &non_nullable).is_err());
+}
+
+#[test]
+fn test_ipc_format_version() {
+    let block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    let schema = DataSchemaRefExt::create(vec![DataField::new(
+        "a",
+        DataType::Number(NumberDataType::Int32),
+    )]);
+
+    let bytes = block.to_ipc(&schema).unwrap();
+    assert_eq!(bytes[0], IPC_FORMAT_VERSION);
+
+    // Buffers written before the version prefix are read as version 0.
+    let v0 = &bytes[1..];
+    let result = DataBlock::from_ipc(v0, &schema).unwrap();
+    assert_eq!(result.columns(), block.columns());
+
+    // A buffer from a newer version is rejected instead of misparsed.
+    let mut future = bytes.clone();
+    future[0] = IPC_FORMAT_VERSION + 1;
+    let err = DataBlock::from_ipc(&future, &schema).unwrap_err();
+    assert!(err.message().contains("unsupported IPC format version"));
+}
+
+#[test]
+fn test_block_entry_display() {
+    let entry = BlockEntry::new(
+        DataType::Number(NumberDataType::Int32),
+        Value::Scalar(Scalar::Number(NumberScalar::Int32(7))),
+    );
+    assert_eq!(entry.to_string(), "Int32 = Scalar(7)");
+
+    let column = Int32Type::from_opt_data(vec![Some(1), None, Some(3)]);
+    let entry = BlockEntry::new(column.data_type(), Value::Column(column));
+    assert_eq!(entry.to_string(), "Int32 NULL = Column[3]: [1, NULL, 3]");
+
+    // Long columns only show the leading values.
+    let column = StringType::from_data(vec!["a", "b", "c", "d", "e", "f", "g"]);
+    let entry = BlockEntry::new(DataType::String, Value::Column(column));
+    assert_eq!(
+        entry.to_string(),
+        "String = Column[7]: ['a', 'b', 'c', 'd', 'e', ...]"
+    );
+}
+
+#[test]
+fn test_avg_row_size() {
+    assert_eq!(DataBlock::empty().avg_row_size(), 0);
+
+    let block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3, 4]),
+        Int64Type::from_data(vec![1, 2, 3, 4]),
+    ]);
+    assert_eq!(block.avg_row_size(), 12);
+
+    // A scalar entry counts for every row.
+    let mut block = block;
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int64),
+        Value::Scalar(Scalar::Number(NumberScalar::Int64(7))),
+    ));
+    assert_eq!(block.avg_row_size(), 20);
+}
+
+#[test]
+fn test_partition_meta() -> Result<()> {
+    let block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    assert_eq!(block.partition_id(), None);
+
+    let block = block.with_partition(3)?;
+    assert_eq!(block.partition_id(), Some(3));
+    assert_eq!(
+        PartitionMeta::downcast_ref_from(block.get_meta().unwrap()),
+        Some(&PartitionMeta { partition_id: 3 })
+    );
+
+    // The meta can be set through `add_meta` as well, but only once.
+    let block = new_block(&[Int32Type::from_data(vec![1, 2, 3])])
+        .add_meta(Some(Box::new(PartitionMeta { partition_id: 7 })))?;
+    assert_eq!(block.partition_id(), Some(7));
+    assert!(block.with_partition(8).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_row() {
+    let mut block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_opt_data(vec![Some("a"), Some("b"), None]),
+    ]);
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int64),
+        Value::Scalar(Scalar::Number(NumberScalar::Int64(7))),
+    ));
+
+    assert_eq!(block.row(2).unwrap(), vec![
+        Scalar::Number(NumberScalar::Int32(3)),
+        Scalar::Null,
+        Scalar::Number(NumberScalar::Int64(7)),
+    ]);
+    assert!(block.row(3).is_err());
+}
+
+#[test]
+fn test_from_scalars() {
+    let block = DataBlock::from_scalars(vec![
+        (
+            DataType::Number(NumberDataType::Int32),
+            Scalar::Number(NumberScalar::Int32(1)),
+        ),
+        (DataType::String, Scalar::String(b"a".to_vec())),
+        (DataType::Nullable(Box::new(DataType::String)), Scalar::Null),
+    ]);
+
+    assert_eq!(block.num_rows(), 1);
+    assert_eq!(block.num_columns(), 3);
+    assert!(
+        block
+            .columns()
+            .iter()
+            .all(|entry| matches!(entry.value, Value::Scalar(_)))
+    );
+    assert_eq!(block.row(0).unwrap(), vec![
+        Scalar::Number(NumberScalar::Int32(1)),
+        Scalar::String(b"a".to_vec()),
+        Scalar::Null,
+    ]);
+}
+
+#[test]
+fn test_block_equals() {
+    let block = new_block(&[
+        Int32Type::from_data(vec![1, 1]),
+        Float64Type::from_data(vec![0.1, 0.2]),
+    ]);
+
+    // A scalar entry equals a column repeating its value.
+    let other = DataBlock::new(
+        vec![
+            BlockEntry::new(
+                DataType::Number(NumberDataType::Int32),
+                Value::Scalar(Scalar::Number(NumberScalar::Int32(1))),
+            ),
+            block.get_by_offset(1).clone(),
+        ],
+        2,
+    );
+    assert!(block.equals(&other));
+    assert_block_eq(&block, &other, 0.0);
+
+    // Different values, types or row counts.
+    let different = new_block(&[
+        Int32Type::from_data(vec![1, 2]),
+        Float64Type::from_data(vec![0.1, 0.2]),
+    ]);
+    assert!(!block.equals(&different));
+    let different = new_block(&[
+        Int64Type::from_data(vec![1, 1]),
+        Float64Type::from_data(vec![0.1, 0.2]),
+    ]);
+    assert!(!block.equals(&different));
+    assert!(!block.equals(&block.slice(0..1)));
+
+    // Floats only compare equal within the given epsilon.
+    let close = new_block(&[
+        Int32Type::from_data(vec![1, 1]),
+        Float64Type::from_data(vec![0.1, 0.2 + 1e-12]),
+    ]);
+    assert!(!block.equals(&close));
+    assert!(block.equals_with_epsilon(&close, 1e-9));
+    assert_block_eq(&block, &close, 1e-9);
+}
+
+#[test]
+fn test_try_get_by_offset() {
+    let block = new_block(&[Int32Type::from_data(vec![1, 2, 3])]);
+    assert_eq!(block.try_get_by_offset(0).unwrap(), block.get_by_offset(0));
+
+    let err = block.try_get_by_offset(1).unwrap_err();
+    assert_eq!(err.code(), ErrorCode::BAD_ARGUMENTS);
+}
+
+#[test]
+fn test_zip() {
+    let left = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ]);
+    let right = new_block(&[
+        Int64Type::from_data(vec![4, 5, 6]),
+        StringType::from_data(vec!["d", "e", "f"]),
+        Int32Type::from_opt_data(vec![Some(7), None, Some(9)]),
+    ]);
+
+    let block = left.clone().zip(right.clone()).unwrap();
+    assert_eq!(block.num_rows(), 3);
+    assert_eq!(block.num_columns(), 5);
+    assert_eq!(&block.columns()[..2], left.columns());
+    assert_eq!(&block.columns()[2..], right.columns());
+
+    // The blocks must have the same number of rows.
+    let short = new_block(&[Int32Type::from_data(vec![1])]);
+    assert!(left.zip(short).is_err());
+}
+
+#[test]
+fn test_iter_rows() {
+    let mut block = new_block(&[
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3), Some(4)]),
+        StringType::from_data(vec!["a", "b", "c", "d"]),
+    ]);
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int64),
+        Value::Scalar(Scalar::Number(NumberScalar::Int64(10))),
+    ));
+
+    let mut sum = 0;
+    let mut num_rows = 0;
+    for row in block.iter_rows() {
+        assert_eq!(row.len(), 3);
+        if let ScalarRef::Number(NumberScalar::Int32(v)) = row[0] {
+            sum += v;
+        }
+        assert_eq!(row[2], ScalarRef::Number(NumberScalar::Int64(10)));
+        num_rows += 1;
+    }
+    assert_eq!(sum, 8);
+    assert_eq!(num_rows, 4);
+
+    let rows = block.iter_rows().collect::<Vec<_>>();
+    assert_eq!(rows[1][0], ScalarRef::Null);
+    assert_eq!(rows[3][1], ScalarRef::String("d"));
+}
+
+#[test]
+fn test_try_into_arrow_chunk() {
+    let int_type = DataType::Number(NumberDataType::Int64);
+
+    // Scalars of an empty block become empty arrays of the column type.
+    let block = DataBlock::new(
+        vec![
+            BlockEntry::new(
+                DataType::String,
+                Value::Column(StringType::from_data(Vec::<&str>::new())),
+            ),
+            BlockEntry::new(
+                int_type.clone(),
+                Value::Scalar(Scalar::Number(NumberScalar::Int64(1))),
+            ),
+        ],
+        0,
+    );
+    let chunk = ArrowChunk::<ArrayRef>::try_from(block).unwrap();
+    assert_eq!(chunk.len(), 0);
+    assert_eq!(chunk.arrays().len(), 2);
+    assert_eq!(chunk.arrays()[1].data_type(), &ArrowDataType::Int64);
+
+    let block = DataBlock::new(
+        vec![BlockEntry::new(
+            int_type.clone(),
+            Value::Scalar(Scalar::Number(NumberScalar::Int64(1))),
+        )],
+        3,
+    );
+    let chunk = ArrowChunk::<ArrayRef>::try_from(block).unwrap();
+    assert_eq!(chunk.len(), 3);
+
+    // A NULL scalar does not fit a non-nullable column. The entry is built directly,
+    // `BlockEntry::new` would already reject it in debug builds.
+    let entry = BlockEntry {
+        data_type: int_type,
+        value: Value::Scalar(Scalar::Null),
+    };
+    let block = DataBlock::new(vec![entry], 2);
+    let err = ArrowChunk::<ArrayRef>::try_from(block).unwrap_err();
+    assert!(err.message().contains("column 0"));
+}
+
+#[test]
+fn test_dedup_consecutive() {
+    let block = new_block(&[
+        Int32Type::from_opt_data(vec![
+            None,
+            None,
+            Some(1),
+            Some(1),
+            Some(1),
+            Some(2),
+            Some(2),
+        ]),
+        StringType::from_data(vec!["a", "a", "a", "b", "b", "b", "b"]),
+        Int64Type::from_data(vec![0, 1, 2, 3, 4, 5, 6]),
+    ]);
+
+    // The first row of each run is kept, NULL keys are one run.
+    let result = block.dedup_consecutive(&[0]).unwrap();
+    assert_eq!(
+        result.get_by_offset(2).value.as_column().unwrap(),
+        &Int64Type::from_data(vec![0, 2, 5])
+    );
+
+    let result = block.dedup_consecutive(&[0, 1]).unwrap();
+    assert_eq!(
+        result.get_by_offset(2).value.as_column().unwrap(),
+        &Int64Type::from_data(vec![0, 2, 3, 5])
+    );
+
+    // Without keys every row is a duplicate of the previous one.
+    assert_eq!(block.dedup_consecutive(&[]).unwrap().num_rows(), 1);
+    assert!(block.dedup_consecutive(&[3]).is_err());
+}
@@ -13,11 +13,31 @@
 // limitations under the License.
 
 use databend_common_arrow::arrow::array::new_empty_array;
+use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_arrow::arrow::datatypes::DataType as ArrowDataType;
+use databend_common_arrow::arrow::datatypes::Field as ArrowField;
 use databend_common_exception::Result;
+use databend_common_expression::types::array::ArrayColumn;
+use databend_common_expression::types::decimal::Decimal128Type;
+use databend_common_expression::types::number::NumberScalar;
+use databend_common_expression::types::number::F64;
+use databend_common_expression::types::BooleanType;
 use databend_common_expression::types::DataType;
+use databend_common_expression::types::DecimalDataType;
+use databend_common_expression::types::DecimalSize;
+use databend_common_expression::types::Float64Type;
+use databend_common_expression::types::Int32Type;
+use databend_common_expression::types::Int64Type;
 use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::StringType;
+use databend_common_expression::types::UInt8Type;
+use databend_common_expression::types::ValueType;
 use databend_common_expression::Column;
+use databend_common_expression::FromData;
+use databend_common_expression::Scalar;
+use databend_common_expression::ScalarRef;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
 
 #[test]
 fn test_from_arrow_extension_to_column() -> Result<()> {
@@ -30,3 +50,270 @@ fn test_from_arrow_extension_to_column() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_from_arrow_array_round_trip() -> Result<()> {
+    let columns = vec![
+        UInt8Type::from_data(vec![1u8, 2, 3]),
+        Int32Type::from_data(vec![-1, 0, 1]),
+        Int64Type::from_data(vec![i64::MIN, 0, i64::MAX]),
+        Float64Type::from_data(vec![0.5f64, -1.5, 3.25]),
+        StringType::from_data(vec!["a", "bc", ""]),
+        BooleanType::from_data(vec![true, false, true]),
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3)]),
+        StringType::from_opt_data(vec![None, Some("x"), None]),
+    ];
+
+    for column in columns {
+        let arrow_col = column.as_arrow();
+        let result = Column::from_arrow_array(arrow_col.as_ref(), &column.data_type())?;
+        assert_eq!(result, column);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_from_arrow_array_validity_mismatch() -> Result<()> {
+    // A non-nullable array read as nullable gets an all-valid validity.
+    let column = Int32Type::from_data(vec![1, 2, 3]);
+    let arrow_col = column.as_arrow();
+    let result = Column::from_arrow_array(arrow_col.as_ref(), &column.data_type().wrap_nullable())?;
+    assert_eq!(
+        result,
+        Int32Type::from_opt_data(vec![Some(1), Some(2), Some(3)])
+    );
+
+    // An array with nulls can't be read as non-nullable.
+    let column = Int32Type::from_opt_data(vec![Some(1), None, Some(3)]);
+    let arrow_col = column.as_arrow();
+    let data_type = DataType::Number(NumberDataType::Int32);
+    assert!(Column::from_arrow_array(arrow_col.as_ref(), &data_type).is_err());
+
+    // An array with an all-valid validity can still be read as non-nullable.
+    let column = Int32Type::from_opt_data(vec![Some(1), Some(2), Some(3)]);
+    let arrow_col = column.as_arrow();
+    let result = Column::from_arrow_array(arrow_col.as_ref(), &data_type)?;
+    assert_eq!(result, Int32Type::from_data(vec![1, 2, 3]));
+
+    Ok(())
+}
+
+#[test]
+fn test_from_arrow_decimal_round_trip() -> Result<()> {
+    let size = DecimalSize {
+        precision: 38,
+        scale: 10,
+    };
+    let column = Decimal128Type::from_opt_data_with_size(
+        vec![
+            Some(12345678901234567890123456789_i128),
+            None,
+            Some(-99999999999999999999999999999999999999_i128),
+        ],
+        size,
+    );
+    let data_type = column.data_type();
+    assert_eq!(
+        data_type,
+        DataType::Decimal(DecimalDataType::Decimal128(size)).wrap_nullable()
+    );
+
+    let arrow_col = column.as_arrow();
+    assert_eq!(arrow_col.data_type(), &ArrowDataType::Decimal(38, 10));
+    let result = Column::from_arrow_array(arrow_col.as_ref(), &data_type)?;
+    assert_eq!(result, column);
+
+    // Decimal128 can't hold more than 38 digits.
+    let field = ArrowField::new("a", ArrowDataType::Decimal(39, 10), false);
+    assert!(TableField::try_from(&field).is_err());
+    let field = ArrowField::new("a", ArrowDataType::Decimal(38, 10), false);
+    assert_eq!(
+        TableField::try_from(&field)?.data_type(),
+        &TableDataType::Decimal(DecimalDataType::Decimal128(size))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_concat() -> Result<()> {
+    let column = Column::concat(&[
+        Int32Type::from_data(vec![1, 2]),
+        Int32Type::from_data(vec![3]),
+    ])?;
+    assert_eq!(column, Int32Type::from_data(vec![1, 2, 3]));
+
+    let column = Column::concat(&[
+        StringType::from_data(vec!["a"]),
+        StringType::from_data(vec!["bc", ""]),
+    ])?;
+    assert_eq!(column, StringType::from_data(vec!["a", "bc", ""]));
+
+    // Non-nullable columns are unified to nullable.
+    let column = Column::concat(&[
+        Int32Type::from_data(vec![1]),
+        Int32Type::from_opt_data(vec![None, Some(2)]),
+        Int32Type::from_data(vec![3]),
+    ])?;
+    assert_eq!(
+        column,
+        Int32Type::from_opt_data(vec![Some(1), None, Some(2), Some(3)])
+    );
+
+    assert!(
+        Column::concat(&[Int32Type::from_data(vec![1]), Int64Type::from_data(vec![2])]).is_err()
+    );
+    assert!(
+        Column::concat(&[
+            StringType::from_data(vec!["a"]),
+            Int32Type::from_opt_data(vec![None])
+        ])
+        .is_err()
+    );
+    assert!(Column::concat(&[]).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_filter_and_take() -> Result<()> {
+    let columns = [
+        Int32Type::from_data(vec![1, 2, 3, 4]),
+        StringType::from_data(vec!["a", "b", "c", "d"]),
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3), None]),
+    ];
+    let filter = Bitmap::from([true, false, false, true]);
+    let expected = [
+        Int32Type::from_data(vec![1, 4]),
+        StringType::from_data(vec!["a", "d"]),
+        Int32Type::from_opt_data(vec![Some(1), None]),
+    ];
+    for (column, expected) in columns.iter().zip(expected.iter()) {
+        assert_eq!(&column.filter(&filter), expected);
+        assert_eq!(&column.checked_take(&[0, 3])?, expected);
+
+        // Empty selections keep the column type.
+        let empty = column.filter(&Bitmap::new_zeroed(4));
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.data_type(), column.data_type());
+        let empty = column.checked_take(&[])?;
+        assert_eq!(empty.len(), 0);
+        assert_eq!(empty.data_type(), column.data_type());
+
+        assert!(column.checked_take(&[1, 4]).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_memory_size() -> Result<()> {
+    // 5 offsets of 8 bytes plus 12 bytes of data.
+    let dense = StringType::from_data(vec!["abc", "def", "ghi", "jkl"]);
+    assert_eq!(dense.memory_size(), 5 * 8 + 12);
+
+    // A slice shares the data buffer, but only its own rows are counted.
+    let sparse = dense.slice(1..2);
+    assert_eq!(sparse.memory_size(), 2 * 8 + 3);
+    let typed = StringType::try_downcast_column(&sparse).unwrap();
+    assert_eq!(StringType::column_memory_size(&typed), sparse.memory_size());
+
+    // The validity bitmap takes one byte for up to 8 rows.
+    let nullable = StringType::from_opt_data(vec![Some("abc"), None, Some("ghi"), Some("jkl")]);
+    assert_eq!(nullable.memory_size(), 5 * 8 + 9 + 1);
+
+    // Arrays count their own offsets and only the values they reference.
+    let array = Column::Array(Box::new(ArrayColumn {
+        values: dense,
+        offsets: vec![0, 2, 4].into(),
+    }));
+    assert_eq!(array.memory_size(), 3 * 8 + 5 * 8 + 12);
+    assert_eq!(array.slice(1..2).memory_size(), 2 * 8 + 3 * 8 + 6);
+
+    Ok(())
+}
+
+#[test]
+fn test_constant() {
+    let string_type = DataType::String;
+    let column = Column::constant(&ScalarRef::String("ab"), 3, &string_type);
+    assert_eq!(column, StringType::from_data(vec!["ab", "ab", "ab"]));
+
+    // A non-NULL scalar of a nullable type is wrapped with all rows valid.
+    let column = Column::constant(&ScalarRef::String("ab"), 2, &string_type.wrap_nullable());
+    assert_eq!(
+        column,
+        StringType::from_opt_data(vec![Some("ab"), Some("ab")])
+    );
+
+    let column = Column::constant(&ScalarRef::Null, 2, &string_type.wrap_nullable());
+    assert_eq!(column, StringType::from_opt_data(vec![None::<&str>, None]));
+    assert_eq!(column.data_type(), string_type.wrap_nullable());
+
+    let column = Column::constant(&ScalarRef::Null, 4, &DataType::Null);
+    assert_eq!(column, Column::Null { len: 4 });
+}
+
+#[test]
+fn test_try_slice() -> Result<()> {
+    let column = StringType::from_opt_data(vec![
+        Some("a"),
+        None,
+        Some("bcd"),
+        Some(""),
+        None,
+        Some("ef"),
+    ]);
+
+    // Values and NULL positions must stay aligned at a non-zero offset.
+    let sliced = column.try_slice(1..5)?;
+    assert_eq!(
+        sliced,
+        StringType::from_opt_data(vec![None, Some("bcd"), Some(""), None])
+    );
+    assert_eq!(sliced.index(0), Some(ScalarRef::Null));
+    assert_eq!(sliced.index(1), Some(ScalarRef::String("bcd")));
+
+    // A slice of a slice keeps both offsets.
+    let sliced = sliced.try_slice(1..4)?;
+    assert_eq!(
+        sliced,
+        StringType::from_opt_data(vec![Some("bcd"), Some(""), None])
+    );
+    let validity = sliced.as_nullable().unwrap().validity.clone();
+    assert_eq!(validity.iter().collect::<Vec<_>>(), vec![true, true, false]);
+
+    // The string offsets point into the shared data buffer.
+    let inner = sliced.remove_nullable();
+    let strings = inner.as_string().unwrap();
+    assert_eq!(strings.offsets()[0], 1);
+    assert_eq!(strings.iter().collect::<Vec<_>>(), vec![
+        b"bcd".as_slice(),
+        b"",
+        b""
+    ]);
+
+    assert!(column.try_slice(2..7).is_err());
+    #[allow(clippy::reversed_empty_ranges)]
+    let reversed = 4..2;
+    assert!(column.try_slice(reversed).is_err());
+    assert_eq!(column.try_slice(6..6)?.len(), 0);
+
+    Ok(())
+}
+
+#[test]
+fn test_float_domain_with_nan() {
+    let float = |v: f64| Scalar::Number(NumberScalar::Float64(F64::from(v)));
+
+    // NaN sorts after every other value, so it is the max but never the min.
+    let column = Float64Type::from_data(vec![1.0, f64::NAN, -2.0, 3.0]);
+    let (min, max) = column.domain().to_minmax();
+    assert_eq!(min, float(-2.0));
+    assert_eq!(max, float(f64::NAN));
+
+    let column = Float64Type::from_data(vec![f64::NAN, f64::NAN]);
+    let (min, max) = column.domain().to_minmax();
+    assert_eq!(min, float(f64::NAN));
+    assert_eq!(max, float(f64::NAN));
+}
@@ -14,6 +14,7 @@
 
 use core::ops::Range;
 
+use databend_common_arrow::arrow::bitmap::Bitmap;
 use databend_common_expression::block_debug::assert_block_value_eq;
 use databend_common_expression::types::number::*;
 use databend_common_expression::types::DataType;
@@ -23,6 +24,7 @@ use databend_common_expression::BlockEntry;
 use databend_common_expression::Column;
 use databend_common_expression::DataBlock;
 use databend_common_expression::FromData;
+use databend_common_expression::Scalar;
 use databend_common_expression::Value;
 use goldenfile::Mint;
 
@@ -375,6 +377,83 @@ pub fn test_take_compact() -> databend_common_exception::Result<()> {
     Ok(())
 }
 
+#[test]
+pub fn test_reverse() -> databend_common_exception::Result<()> {
+    let mut block = new_block(&[
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_opt_data(vec![Some("a"), None, Some("c")]),
+    ]);
+    block.add_column(BlockEntry::new(
+        DataType::Number(NumberDataType::Int64),
+        Value::Scalar(Scalar::Number(NumberScalar::Int64(7))),
+    ));
+
+    let reversed = block.reverse()?;
+    assert_eq!(reversed.num_rows(), 3);
+    assert_eq!(reversed.row(2)?, block.row(0)?);
+    assert_eq!(reversed.row(0)?, block.row(2)?);
+    assert_eq!(reversed.get_by_offset(2), block.get_by_offset(2));
+    assert_block_value_eq(&reversed.reverse()?, &block);
+
+    Ok(())
+}
+
+#[test]
+pub fn test_take_sorted_ranges() -> databend_common_exception::Result<()> {
+    let block = new_block(&[
+        Int32Type::from_data((0..10).collect::<Vec<_>>()),
+        StringType::from_data((0..10).map(|i| i.to_string()).collect::<Vec<_>>()),
+    ]);
+
+    let ranges = [1..3, 4..5, 7..10];
+    let result = block.take_sorted_ranges(&ranges)?;
+    let bitmap = (0..10)
+        .map(|i| ranges.iter().any(|range| range.contains(&i)))
+        .collect::<Bitmap>();
+    let expected = block.clone().filter_with_bitmap(&bitmap)?;
+    assert_eq!(result.num_rows(), 6);
+    assert_block_value_eq(&result, &expected);
+
+    // Overlapping, unsorted and out of bounds ranges are rejected.
+    assert!(block.take_sorted_ranges(&[1..4, 3..5]).is_err());
+    assert!(block.take_sorted_ranges(&[4..5, 1..3]).is_err());
+    assert!(block.take_sorted_ranges(&[8..11]).is_err());
+
+    Ok(())
+}
+
+#[test]
+pub fn test_scatter_partitions() -> databend_common_exception::Result<()> {
+    let block = new_block(&[
+        Int32Type::from_data((0..8).collect::<Vec<_>>()),
+        StringType::from_data((0..8).map(|i| i.to_string()).collect::<Vec<_>>()),
+    ]);
+
+    let partition_ids = [2u32, 0, 1, 2, 0, 0, 2, 1];
+    let partitions = block.scatter(&partition_ids, 3)?;
+    assert_eq!(partitions.len(), 3);
+    assert_eq!(
+        partitions.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![3, 2, 3]
+    );
+    assert_block_value_eq(
+        &partitions[0],
+        &new_block(&[
+            Int32Type::from_data(vec![1, 4, 5]),
+            StringType::from_data(vec!["1", "4", "5"]),
+        ]),
+    );
+
+    // Concatenating the partitions gives back every row, grouped by partition.
+    let expected = block.take(&[1u32, 4, 5, 2, 7, 0, 3, 6], &mut None)?;
+    assert_block_value_eq(&DataBlock::concat(&partitions)?, &expected);
+
+    assert!(block.scatter(&partition_ids[1..], 3).is_err());
+    assert!(block.scatter(&[0u32, 0, 1, 3, 0, 0, 2, 1], 3).is_err());
+
+    Ok(())
+}
+
 /// Random Block A
 /// +----+----+----+----+----+----+----+----+----+----+
 /// B = A + A + A,  l = A.len()
@@ -12,16 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::io::Cursor;
 use std::vec;
 
 use borsh::BorshDeserialize;
 use borsh::BorshSerialize;
+use databend_common_arrow::arrow::io::flight::deserialize_message;
+use databend_common_arrow::arrow::io::flight::deserialize_schemas;
+use databend_common_arrow::arrow::io::ipc::read::read_file_metadata;
+use databend_common_arrow::arrow::io::ipc::read::FileReader;
 use databend_common_exception::Result;
+use databend_common_expression::arrow::column_to_arrow_array_with_field;
 use databend_common_expression::arrow::deserialize_column;
+use databend_common_expression::arrow::deserialize_columns;
 use databend_common_expression::arrow::serialize_column;
+use databend_common_expression::arrow::serialize_flight_data;
+use databend_common_expression::arrow::ArrowSpillWriter;
 use databend_common_expression::types::DataType;
+use databend_common_expression::types::Int32Type;
+use databend_common_expression::types::NumberDataType;
 use databend_common_expression::types::StringType;
 use databend_common_expression::Column;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchema;
 use databend_common_expression::FromData;
 use databend_common_expression::RemoteExpr;
 use databend_common_expression::Scalar;
@@ -87,6 +101,141 @@ fn test_serde_bin_column() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_deserialize_multi_batch_columns() -> Result<()> {
+    let schema = DataSchema::new(vec![
+        DataField::new("a", DataType::Number(NumberDataType::Int32)),
+        DataField::new("b", DataType::String),
+    ]);
+
+    let mut writer = ArrowSpillWriter::try_create(schema.fields())?;
+    writer.write(&DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2]),
+        StringType::from_data(vec!["a", "b"]),
+    ]))?;
+    writer.write(&DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![3]),
+        StringType::from_data(vec!["c"]),
+    ]))?;
+    let data = writer.finish()?;
+
+    let columns = deserialize_columns(&data)?;
+    assert_eq!(columns, vec![
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_data(vec!["a", "b", "c"]),
+    ]);
+
+    // The single column helper refuses to drop the other columns.
+    assert!(deserialize_column(&data).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_arrow_spill_writer() -> Result<()> {
+    let schema = DataSchema::new(vec![
+        DataField::new("a", DataType::Number(NumberDataType::Int32)),
+        DataField::new("b", DataType::String),
+    ]);
+
+    let blocks = (0..100)
+        .map(|i| {
+            let strings = [format!("{i}"), format!("{i}x"), format!("{i}xx")];
+            DataBlock::new_from_columns(vec![
+                Int32Type::from_data(vec![i, i + 1, i + 2]),
+                StringType::from_data(strings.iter().map(|s| s.as_str()).collect::<Vec<_>>()),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+    let mut writer = ArrowSpillWriter::try_create(schema.fields())?;
+    for block in blocks.iter() {
+        writer.write(block)?;
+    }
+    let data = writer.finish()?;
+
+    let mut cursor = Cursor::new(&data);
+    let metadata = read_file_metadata(&mut cursor)?;
+    assert_eq!(metadata.schema.fields.len(), 2);
+    assert_eq!(metadata.blocks.len(), 100);
+
+    let reader = FileReader::new(cursor, metadata, None, None);
+    let mut num_blocks = 0;
+    for (chunk, expected) in reader.zip(blocks.iter()) {
+        let block = DataBlock::from_arrow_chunk(&chunk?, &schema)?;
+        assert_eq!(block.num_rows(), expected.num_rows());
+        for (col, expected_col) in block.columns().iter().zip(expected.columns()) {
+            assert_eq!(col, expected_col);
+        }
+        num_blocks += 1;
+    }
+    assert_eq!(num_blocks, 100);
+
+    // Writing a block with a different number of columns is rejected.
+    let mut writer = ArrowSpillWriter::try_create(schema.fields())?;
+    let block = DataBlock::new_from_columns(vec![Int32Type::from_data(vec![1, 2, 3])]);
+    assert!(writer.write(&block).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_serialize_flight_data() -> Result<()> {
+    let schema = DataSchema::new(vec![
+        DataField::new("a", DataType::Number(NumberDataType::Int32)),
+        DataField::new("b", DataType::String.wrap_nullable()),
+    ]);
+    let block = DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2, 3]),
+        StringType::from_opt_data(vec![Some("x"), None, Some("数据")]),
+    ]);
+
+    let messages = serialize_flight_data(block.clone(), schema.fields(), &Default::default())?;
+    assert_eq!(messages.len(), 2);
+
+    let (arrow_schema, ipc_schema) = deserialize_schemas(&messages[0].data_header)?;
+    let mut dictionaries = Default::default();
+    let mut chunks = vec![];
+    for message in &messages[1..] {
+        if let Some(chunk) = deserialize_message(
+            message,
+            &arrow_schema.fields,
+            &ipc_schema,
+            &mut dictionaries,
+        )? {
+            chunks.push(chunk);
+        }
+    }
+    assert_eq!(chunks.len(), 1);
+
+    let decoded = DataBlock::from_arrow_chunk(&chunks[0], &schema)?;
+    assert_eq!(decoded.num_rows(), 3);
+    for (col, expected_col) in decoded.columns().iter().zip(block.columns()) {
+        assert_eq!(col, expected_col);
+    }
+
+    // The fields must match the columns of the block.
+    assert!(serialize_flight_data(block, &schema.fields()[..1], &Default::default()).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_column_to_arrow_array_with_field() {
+    let block = DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2, 3]),
+        Int32Type::from_opt_data(vec![Some(1), None, Some(3)]),
+    ]);
+
+    let (array, field) = column_to_arrow_array_with_field(block.get_by_offset(0), "a", 3);
+    assert_eq!(field.name, "a");
+    assert!(!field.is_nullable);
+    assert_eq!(array.len(), 3);
+    assert_eq!(array.data_type(), &field.data_type);
+
+    let (array, field) = column_to_arrow_array_with_field(block.get_by_offset(1), "b", 3);
+    assert_eq!(field.name, "b");
+    assert!(field.is_nullable);
+    assert_eq!(array.null_count(), 1);
+}
+
 #[test]
 fn test_borsh_serde_column() -> Result<()> {
     #[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug)]
@@ -203,6 +203,69 @@ fn test_block_sort() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_block_top_n() -> Result<()> {
+    let block = new_block(&[
+        Int64Type::from_data(vec![6i64, 4, 3, 2, 1, 1, 7, 4]),
+        StringType::from_data(vec!["b1", "b2", "b3", "b4", "b5", "b6", "b7", "b8"]),
+    ]);
+
+    let descriptions = [
+        vec![
+            SortColumnDescription {
+                offset: 0,
+                asc: true,
+                nulls_first: false,
+                is_nullable: false,
+            },
+            SortColumnDescription {
+                offset: 1,
+                asc: false,
+                nulls_first: false,
+                is_nullable: false,
+            },
+        ],
+        vec![
+            SortColumnDescription {
+                offset: 0,
+                asc: false,
+                nulls_first: false,
+                is_nullable: false,
+            },
+            SortColumnDescription {
+                offset: 1,
+                asc: true,
+                nulls_first: false,
+                is_nullable: false,
+            },
+        ],
+    ];
+    for descriptions in descriptions.iter() {
+        let sorted = DataBlock::sort(&block, descriptions, None)?;
+        for n in [0, 1, 3, 7, 8, 10] {
+            let top_n = DataBlock::top_n(&block, descriptions, n)?;
+            let expected = sorted.slice(0..n.min(block.num_rows()));
+            assert_eq!(top_n.num_rows(), expected.num_rows());
+            assert_eq!(top_n.columns(), expected.columns(), "n: {n}");
+        }
+    }
+
+    // Rows with equal keys keep their original order.
+    let descriptions = vec![SortColumnDescription {
+        offset: 0,
+        asc: true,
+        nulls_first: false,
+        is_nullable: false,
+    }];
+    let top_n = DataBlock::top_n(&block, &descriptions, 5)?;
+    assert_eq!(
+        top_n.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_data(vec!["b5", "b6", "b4", "b3", "b2"])
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_blocks_merge_sort() -> Result<()> {
     let blocks = vec![
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use chrono_tz::Tz;
+use databend_common_io::prelude::FloatFormat;
 use databend_common_meta_app::principal::BinaryFormat;
 
 #[derive(Clone)]
@@ -36,4 +37,5 @@ pub struct OutputCommonSettings {
     pub inf_bytes: Vec<u8>,
     pub timezone: Tz,
     pub binary_format: BinaryFormat,
+    pub float_format: FloatFormat,
 }
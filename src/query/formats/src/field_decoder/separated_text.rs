@@ -261,6 +261,21 @@ impl SeparatedTextDecoder {
         T: Number + From<T::Native>,
         T::Native: FromLexical,
     {
+        // Map the configured NaN/inf tokens to the literals understood by the number parser,
+        // scientific notation like `1.5e10` or `-3E-4` is accepted as is.
+        let settings = self.common_settings();
+        let data = if data.eq_ignore_ascii_case(&settings.nan_bytes) {
+            NAN_BYTES_LOWER.as_bytes()
+        } else if data.eq_ignore_ascii_case(&settings.inf_bytes) {
+            INF_BYTES_LOWER.as_bytes()
+        } else if data.len() > 1
+            && data[0] == b'-'
+            && data[1..].eq_ignore_ascii_case(&settings.inf_bytes)
+        {
+            b"-inf"
+        } else {
+            data
+        };
         let v: T::Native = read_num_text_exact(data)?;
         column.push(v.into());
         Ok(())
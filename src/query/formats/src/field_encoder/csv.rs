@@ -21,6 +21,7 @@ use databend_common_io::constants::INF_BYTES_LOWER;
 use databend_common_io::constants::NULL_BYTES_ESCAPE;
 use databend_common_io::constants::TRUE_BYTES_LOWER;
 use databend_common_io::constants::TRUE_BYTES_NUM;
+use databend_common_io::prelude::FloatFormat;
 use databend_common_meta_app::principal::CsvFileFormatParams;
 use databend_common_meta_app::principal::TsvFileFormatParams;
 
@@ -82,11 +83,17 @@ impl FieldEncoderCSV {
                 common_settings: OutputCommonSettings {
                     true_bytes: TRUE_BYTES_LOWER.as_bytes().to_vec(),
                     false_bytes: FALSE_BYTES_LOWER.as_bytes().to_vec(),
-                    null_bytes: NULL_BYTES_ESCAPE.as_bytes().to_vec(),
+                    null_bytes: options_ext
+                        .null_string
+                        .as_deref()
+                        .unwrap_or(NULL_BYTES_ESCAPE)
+                        .as_bytes()
+                        .to_vec(),
                     nan_bytes: params.nan_display.as_bytes().to_vec(),
                     inf_bytes: INF_BYTES_LOWER.as_bytes().to_vec(),
                     timezone: options_ext.timezone,
                     binary_format: params.binary_format,
+                    float_format: FloatFormat::default(),
                 },
                 quote_char: 0, // not used
             },
@@ -103,11 +110,17 @@ impl FieldEncoderCSV {
                 common_settings: OutputCommonSettings {
                     true_bytes: TRUE_BYTES_NUM.as_bytes().to_vec(),
                     false_bytes: FALSE_BYTES_NUM.as_bytes().to_vec(),
-                    null_bytes: NULL_BYTES_ESCAPE.as_bytes().to_vec(),
+                    null_bytes: options_ext
+                        .null_string
+                        .as_deref()
+                        .unwrap_or(NULL_BYTES_ESCAPE)
+                        .as_bytes()
+                        .to_vec(),
                     nan_bytes: params.nan_display.as_bytes().to_vec(),
                     inf_bytes: INF_BYTES_LOWER.as_bytes().to_vec(),
                     timezone: options_ext.timezone,
                     binary_format: Default::default(),
+                    float_format: FloatFormat::default(),
                 },
                 quote_char: 0, // not used
             },
@@ -14,6 +14,8 @@
 
 use std::num::FpCategory;
 
+use databend_common_io::prelude::FloatFormat;
+
 use crate::OutputCommonSettings;
 
 // 30% faster lexical_core::write to tmp buf and extend_from_slice
@@ -46,9 +48,16 @@ macro_rules! impl_float {
                     FpCategory::Infinite => {
                         buf.extend_from_slice(&settings.inf_bytes);
                     }
-                    _ => {
-                        extend_lexical(self, buf);
-                    }
+                    _ => match settings.float_format {
+                        // lexical writes the shortest text that round-trips.
+                        FloatFormat::Shortest => extend_lexical(self, buf),
+                        FloatFormat::Fixed(n) => {
+                            buf.extend_from_slice(format!("{:.n$}", self).as_bytes())
+                        }
+                        FloatFormat::Scientific(n) => {
+                            buf.extend_from_slice(format!("{:.n$e}", self).as_bytes())
+                        }
+                    },
                 }
             }
         }
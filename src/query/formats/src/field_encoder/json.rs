@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use chrono_tz::Tz;
 use databend_common_expression::types::array::ArrayColumn;
 use databend_common_expression::types::nullable::NullableColumn;
 use databend_common_expression::types::ValueType;
@@ -19,6 +20,7 @@ use databend_common_expression::Column;
 use databend_common_io::constants::FALSE_BYTES_LOWER;
 use databend_common_io::constants::NULL_BYTES_LOWER;
 use databend_common_io::constants::TRUE_BYTES_LOWER;
+use databend_common_io::prelude::FloatFormat;
 
 use crate::field_encoder::helpers::write_json_string;
 use crate::field_encoder::FieldEncoderValues;
@@ -43,6 +45,7 @@ impl FieldEncoderJSON {
                     null_bytes: NULL_BYTES_LOWER.as_bytes().to_vec(),
                     timezone: options.timezone,
                     binary_format: Default::default(),
+                    float_format: FloatFormat::default(),
                 },
                 quote_char: 0,
             },
@@ -50,10 +53,53 @@ impl FieldEncoderJSON {
             escape_forward_slashes: true,
         }
     }
+
+    /// Used by the mysql handler to render `Map` values as JSON object text,
+    /// e.g. `{"k1":1,"k2":2}`. Keys of non-string types are quoted as well.
+    pub fn create_for_mysql_handler(timezone: Tz) -> Self {
+        FieldEncoderJSON {
+            simple: FieldEncoderValues {
+                common_settings: OutputCommonSettings {
+                    true_bytes: TRUE_BYTES_LOWER.as_bytes().to_vec(),
+                    false_bytes: FALSE_BYTES_LOWER.as_bytes().to_vec(),
+                    nan_bytes: NULL_BYTES_LOWER.as_bytes().to_vec(),
+                    inf_bytes: NULL_BYTES_LOWER.as_bytes().to_vec(),
+                    null_bytes: NULL_BYTES_LOWER.as_bytes().to_vec(),
+                    timezone,
+                    binary_format: Default::default(),
+                    float_format: FloatFormat::default(),
+                },
+                quote_char: 0,
+            },
+            quote_denormals: false,
+            escape_forward_slashes: false,
+        }
+    }
 }
 
 impl FieldEncoderJSON {
-    pub(crate) fn write_field(&self, column: &Column, row_index: usize, out_buf: &mut Vec<u8>) {
+    /// Write row `row_index` of `columns` as a JSON object keyed by `names`,
+    /// e.g. `{"id":1,"name":"a","tags":null}`.
+    pub fn write_row(
+        &self,
+        columns: &[Column],
+        names: &[&str],
+        row_index: usize,
+        out_buf: &mut Vec<u8>,
+    ) {
+        out_buf.push(b'{');
+        for (i, (column, name)) in columns.iter().zip(names).enumerate() {
+            if i > 0 {
+                out_buf.push(b',');
+            }
+            self.write_string(name.as_bytes(), out_buf);
+            out_buf.push(b':');
+            self.write_field(column, row_index, out_buf);
+        }
+        out_buf.push(b'}');
+    }
+
+    pub fn write_field(&self, column: &Column, row_index: usize, out_buf: &mut Vec<u8>) {
         match &column {
             Column::Nullable(box c) => self.write_nullable(c, row_index, out_buf),
 
@@ -149,7 +195,11 @@ impl FieldEncoderJSON {
                     if i != start {
                         out_buf.extend_from_slice(b",");
                     }
-                    self.write_field(&fields[0], i, out_buf);
+                    // JSON object keys must be strings, so keys of any type are
+                    // rendered as text and quoted, e.g. `{"100":"abc"}`.
+                    let mut key = Vec::new();
+                    self.simple.write_field(&fields[0], i, &mut key, false);
+                    self.write_string(&key, out_buf);
                     out_buf.extend_from_slice(b":");
                     self.write_field(&fields[1], i, out_buf);
                 }
@@ -46,6 +46,8 @@ pub struct FileFormatOptionsExt {
     pub timezone: Tz,
     pub is_select: bool,
     pub is_clickhouse: bool,
+    /// Overrides how NULL is written by the CSV and TSV encoders, from the `null_string` setting.
+    pub null_string: Option<String>,
 }
 
 impl FileFormatOptionsExt {
@@ -63,6 +65,7 @@ impl FileFormatOptionsExt {
             timezone,
             is_select,
             is_clickhouse: false,
+            null_string: parse_null_string(settings)?,
         };
         Ok(options)
     }
@@ -81,6 +84,7 @@ impl FileFormatOptionsExt {
             timezone,
             is_select: false,
             is_clickhouse: true,
+            null_string: parse_null_string(settings)?,
         };
         let suf = &clickhouse_type.suffixes;
         options.headers = suf.headers;
@@ -193,6 +197,12 @@ impl FileFormatTypeExt for StageFileFormatType {
     }
 }
 
+/// An empty `null_string` setting keeps the default NULL text of the format.
+fn parse_null_string(settings: &Settings) -> Result<Option<String>> {
+    let null_string = settings.get_null_string()?;
+    Ok((!null_string.is_empty()).then_some(null_string))
+}
+
 pub fn parse_timezone(settings: &Settings) -> Result<Tz> {
     let tz = settings.get_timezone()?;
     tz.parse::<Tz>()
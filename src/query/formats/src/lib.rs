@@ -25,6 +25,7 @@ mod field_decoder;
 pub mod field_encoder;
 mod file_format_type;
 pub mod output_format;
+pub mod result_writer;
 
 pub use clickhouse::ClickhouseFormatType;
 pub use delimiter::RecordDelimiter;
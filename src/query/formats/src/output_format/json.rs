@@ -17,6 +17,8 @@ use databend_common_expression::types::number::NumberScalar;
 use databend_common_expression::DataBlock;
 use databend_common_expression::ScalarRef;
 use databend_common_expression::TableSchemaRef;
+use databend_common_io::constants::NULL_BYTES_UPPER;
+use databend_common_io::prelude::FloatFormat;
 use databend_common_io::prelude::FormatSettings;
 use roaring::RoaringTreemap;
 use serde_json::Map as JsonMap;
@@ -42,6 +44,9 @@ impl JSONOutputFormat {
             rows: 0,
             format_settings: FormatSettings {
                 timezone: options.timezone,
+                null_string: NULL_BYTES_UPPER.to_string(),
+                float_format: FloatFormat::default(),
+                quote_strings: false,
             },
         }
     }
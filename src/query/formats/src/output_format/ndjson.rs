@@ -67,7 +67,7 @@ impl<const STRINGS: bool, const COMPACT: bool, const WITH_NAMES: bool, const WIT
             .schema
             .fields()
             .iter()
-            .map(|f| f.name().as_bytes())
+            .map(|f| f.name().as_str())
             .collect();
 
         let columns: Vec<Column> = block
@@ -78,6 +78,12 @@ impl<const STRINGS: bool, const COMPACT: bool, const WITH_NAMES: bool, const WIT
             .collect();
 
         for row_index in 0..rows_size {
+            if !COMPACT && !STRINGS {
+                self.field_encoder
+                    .write_row(&columns, &field_names, row_index, &mut buf);
+                buf.push(b'\n');
+                continue;
+            }
             if COMPACT {
                 buf.push(b'[');
             } else {
@@ -88,10 +94,8 @@ impl<const STRINGS: bool, const COMPACT: bool, const WITH_NAMES: bool, const WIT
                     buf.push(b',');
                 }
                 if !COMPACT {
-                    buf.push(b'"');
-                    buf.extend_from_slice(field_names[col_index]);
-                    buf.push(b'"');
-
+                    self.field_encoder
+                        .write_string(field_names[col_index].as_bytes(), &mut buf);
                     buf.push(b':');
                 }
 
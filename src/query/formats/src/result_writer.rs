@@ -0,0 +1,109 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataSchemaRef;
+use serde_json::json;
+
+use crate::field_encoder::FieldEncoderJSON;
+use crate::FileFormatOptionsExt;
+
+/// Emits a query result as a schema header followed by its rows, like the mysql
+/// handler sends the column definitions before the first row.
+pub trait ResultFormatWriter {
+    /// Write the header describing the columns, before any block.
+    fn write_schema(&mut self, schema: &DataSchemaRef) -> Result<()>;
+
+    /// Write all the rows of `block`, its columns follow the order of the schema.
+    fn write_block(&mut self, block: &DataBlock) -> Result<()>;
+}
+
+/// Writes one JSON document per line: first `{"schema":[{"name":..,"type":..},..]}`,
+/// then one object per row keyed by the column names, e.g. `{"id":1,"name":"a"}`.
+pub struct JsonResultWriter<W: Write> {
+    writer: W,
+    encoder: FieldEncoderJSON,
+    names: Option<Vec<String>>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> JsonResultWriter<W> {
+    pub fn create(writer: W, options: &FileFormatOptionsExt) -> Self {
+        JsonResultWriter {
+            writer,
+            encoder: FieldEncoderJSON::create(options),
+            names: None,
+            buf: vec![],
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> ResultFormatWriter for JsonResultWriter<W> {
+    fn write_schema(&mut self, schema: &DataSchemaRef) -> Result<()> {
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| json!({"name": field.name(), "type": field.data_type().wrapped_display()}))
+            .collect::<Vec<_>>();
+        serde_json::to_writer(&mut self.writer, &json!({ "schema": fields }))?;
+        self.writer.write_all(b"\n")?;
+
+        self.names = Some(
+            schema
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect(),
+        );
+        Ok(())
+    }
+
+    fn write_block(&mut self, block: &DataBlock) -> Result<()> {
+        let Some(names) = &self.names else {
+            return Err(ErrorCode::Internal(
+                "the schema must be written before the first block",
+            ));
+        };
+        if names.len() != block.num_columns() {
+            return Err(ErrorCode::BadArguments(format!(
+                "expected {} columns in the block, but got {}",
+                names.len(),
+                block.num_columns()
+            )));
+        }
+
+        let names = names.iter().map(|name| name.as_str()).collect::<Vec<_>>();
+        let columns = block
+            .convert_to_full()
+            .columns()
+            .iter()
+            .map(|entry| entry.value.as_column().unwrap().clone())
+            .collect::<Vec<_>>();
+        for row in 0..block.num_rows() {
+            self.buf.clear();
+            self.encoder.write_row(&columns, &names, row, &mut self.buf);
+            self.buf.push(b'\n');
+            self.writer.write_all(&self.buf)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_exception::Result;
+use databend_common_expression::types::array::ArrayColumn;
+use databend_common_expression::types::BooleanType;
+use databend_common_expression::types::Float64Type;
+use databend_common_expression::types::Int32Type;
+use databend_common_expression::types::Int64Type;
+use databend_common_expression::types::StringType;
+use databend_common_expression::types::VariantType;
+use databend_common_expression::Column;
+use databend_common_expression::FromData;
+use databend_common_formats::field_encoder::FieldEncoderJSON;
+use databend_common_formats::FileFormatOptionsExt;
+use databend_common_settings::Settings;
+
+#[test]
+fn test_mysql_map_as_json() {
+    let keys = StringType::from_data(vec!["k1", "k2"]);
+    let values = Int32Type::from_data(vec![1, 2]);
+    // row 0: {"k1":1,"k2":2}, row 1: {}
+    let column = Column::Map(Box::new(ArrayColumn {
+        values: Column::Tuple(vec![keys, values]),
+        offsets: vec![0, 2, 2].into(),
+    }));
+
+    let encoder = FieldEncoderJSON::create_for_mysql_handler(chrono_tz::UTC);
+
+    let mut buf = vec![];
+    encoder.write_field(&column, 0, &mut buf);
+    assert_eq!(&buf, br#"{"k1":1,"k2":2}"#);
+
+    buf.clear();
+    encoder.write_field(&column, 1, &mut buf);
+    assert_eq!(&buf, b"{}");
+}
+
+#[test]
+fn test_write_row() -> Result<()> {
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+    let encoder = FieldEncoderJSON::create(&options);
+
+    let variant = jsonb::parse_value(br#"{"a":[1,"x"]}"#).unwrap().to_vec();
+    let columns = vec![
+        Int32Type::from_data(vec![1, -2]),
+        Float64Type::from_data(vec![1.5, 2.25]),
+        BooleanType::from_data(vec![true, false]),
+        StringType::from_data(vec!["a\"b", "c\nd"]),
+        Int32Type::from_opt_data(vec![Some(3), None]),
+        // row 0: [1,2], row 1: []
+        Column::Array(Box::new(ArrayColumn {
+            values: Int32Type::from_data(vec![1, 2]),
+            offsets: vec![0, 2, 2].into(),
+        })),
+        // row 0: {"k":["x"]}, row 1: {}
+        Column::Map(Box::new(ArrayColumn {
+            values: Column::Tuple(vec![
+                StringType::from_data(vec!["k"]),
+                Column::Array(Box::new(ArrayColumn {
+                    values: StringType::from_data(vec!["x"]),
+                    offsets: vec![0, 1].into(),
+                })),
+            ]),
+            offsets: vec![0, 1, 1].into(),
+        })),
+        Column::Tuple(vec![
+            Int32Type::from_data(vec![1, 2]),
+            StringType::from_opt_data(vec![Some("t"), None]),
+        ]),
+        VariantType::from_data(vec![variant.clone(), variant]),
+    ];
+    let names = [
+        "int",
+        "float",
+        "bool",
+        "string",
+        "nullable",
+        "array",
+        "map",
+        "tuple",
+        "var\"iant",
+    ];
+
+    let mut buf = vec![];
+    encoder.write_row(&columns, &names, 0, &mut buf);
+    assert_eq!(
+        String::from_utf8(buf)?,
+        r#"{"int":1,"float":1.5,"bool":true,"string":"a\"b","nullable":3,"array":[1,2],"map":{"k":["x"]},"tuple":{"1":1,"2":"t"},"var\"iant":{"a":[1,"x"]}}"#
+    );
+
+    let mut buf = vec![];
+    encoder.write_row(&columns, &names, 1, &mut buf);
+    assert_eq!(
+        String::from_utf8(buf)?,
+        r#"{"int":-2,"float":2.25,"bool":false,"string":"c\nd","nullable":null,"array":[],"map":{},"tuple":{"1":2,"2":null},"var\"iant":{"a":[1,"x"]}}"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mysql_map_int_keys_as_json() {
+    let keys = Int64Type::from_data(vec![100, 200]);
+    let values = StringType::from_data(vec!["abc", "def"]);
+    let column = Column::Map(Box::new(ArrayColumn {
+        values: Column::Tuple(vec![keys, values]),
+        offsets: vec![0, 2].into(),
+    }));
+
+    let encoder = FieldEncoderJSON::create_for_mysql_handler(chrono_tz::UTC);
+
+    let mut buf = vec![];
+    encoder.write_field(&column, 0, &mut buf);
+    assert_eq!(&buf, br#"{"100":"abc","200":"def"}"#);
+}
@@ -13,3 +13,5 @@
 // limitations under the License.
 
 mod helpers;
+mod json;
+mod values;
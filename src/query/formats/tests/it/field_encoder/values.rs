@@ -0,0 +1,61 @@
+// Copyright 2022 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_exception::Result;
+use databend_common_expression::types::Float64Type;
+use databend_common_expression::FromData;
+use databend_common_formats::field_encoder::FieldEncoderValues;
+use databend_common_io::prelude::FloatFormat;
+use databend_common_io::prelude::FormatSettings;
+
+fn write_floats(values: Vec<f64>, float_format: FloatFormat) -> Vec<String> {
+    let format = FormatSettings {
+        float_format,
+        ..FormatSettings::default()
+    };
+    let encoder = FieldEncoderValues::create_for_http_handler(&format);
+    let column = Float64Type::from_data(values.clone());
+    (0..values.len())
+        .map(|row| {
+            let mut buf = vec![];
+            encoder.write_field(&column, row, &mut buf, false);
+            String::from_utf8(buf).unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_float_format() -> Result<()> {
+    // 0.1 + 0.2 needs 17 significant digits to round-trip.
+    let values = vec![0.1, 1e308, 0.1 + 0.2];
+
+    let shortest = write_floats(values.clone(), FloatFormat::Shortest);
+    assert_eq!(shortest[0], "0.1");
+    assert_eq!(shortest[2], "0.30000000000000004");
+    for (text, value) in shortest.iter().zip(values.iter()) {
+        assert_eq!(text.parse::<f64>().unwrap().to_bits(), value.to_bits());
+    }
+
+    let fixed = write_floats(vec![0.1, 0.1 + 0.2], "fixed(3)".parse()?);
+    assert_eq!(fixed, vec!["0.100", "0.300"]);
+
+    let scientific = write_floats(values, "scientific(2)".parse()?);
+    assert_eq!(scientific, vec!["1.00e-1", "1.00e308", "3.00e-1"]);
+
+    assert!("fixed(x)".parse::<FloatFormat>().is_err());
+    assert!("fixed(17)".parse::<FloatFormat>().is_ok());
+    assert!("fixed(100000000)".parse::<FloatFormat>().is_err());
+    assert!("scientific(18)".parse::<FloatFormat>().is_err());
+    Ok(())
+}
@@ -24,6 +24,7 @@ mod field_encoder;
 mod output_format_json_each_row;
 mod output_format_tcsv;
 mod output_format_utils;
+mod result_writer;
 
 fn get_output_format_clickhouse(
     format_name: &str,
@@ -111,6 +111,42 @@ fn test_null() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_null_string_setting() -> Result<()> {
+    let (schema, block) = gen_schema_and_block(
+        vec![
+            TableField::new(
+                "c1",
+                TableDataType::Number(NumberDataType::Int32).wrap_nullable(),
+            ),
+            TableField::new(
+                "c2",
+                TableDataType::Number(NumberDataType::Int32).wrap_nullable(),
+            ),
+        ],
+        vec![
+            Int32Type::from_opt_data(vec![Some(1i32), None]),
+            Int32Type::from_opt_data(vec![None, Some(2i32)]),
+        ],
+    );
+
+    let settings = Settings::create("default".to_string());
+    settings
+        .set_setting("null_string".to_string(), "NULL".to_string())
+        .await?;
+
+    for (typ, expect) in [("csv", "1,NULL\nNULL,2\n"), ("tsv", "1\tNULL\nNULL\t2\n")] {
+        let mut options = BTreeMap::<String, String>::new();
+        options.insert("type".to_string(), typ.to_string());
+        let params = FileFormatParams::try_from_ast(FileFormatOptionsAst::new(options), false)?;
+        let mut options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+        let mut output_format = options.get_output_format(schema.clone(), params)?;
+        let buffer = output_format.serialize_block(&block)?;
+        assert_eq!(&String::from_utf8(buffer)?, expect);
+    }
+    Ok(())
+}
+
 #[test]
 fn test_data_block_nullable() -> Result<()> {
     test_data_block(true)
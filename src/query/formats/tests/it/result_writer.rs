@@ -0,0 +1,63 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_exception::Result;
+use databend_common_expression::types::number::Int32Type;
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::StringType;
+use databend_common_expression::DataBlock;
+use databend_common_expression::DataField;
+use databend_common_expression::DataSchemaRefExt;
+use databend_common_expression::FromData;
+use databend_common_formats::result_writer::JsonResultWriter;
+use databend_common_formats::result_writer::ResultFormatWriter;
+use databend_common_formats::FileFormatOptionsExt;
+use databend_common_settings::Settings;
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_json_result_writer() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![
+        DataField::new("id", DataType::Number(NumberDataType::Int32)),
+        DataField::new_nullable("name", DataType::String),
+    ]);
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    // Blocks can only be written after the schema.
+    let block = DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![1, 2]),
+        StringType::from_opt_data(vec![Some("a\"b"), None]),
+    ]);
+    let mut writer = JsonResultWriter::create(vec![], &options);
+    assert!(writer.write_block(&block).is_err());
+
+    writer.write_schema(&schema)?;
+    writer.write_block(&block)?;
+    writer.write_block(&DataBlock::new_from_columns(vec![
+        Int32Type::from_data(vec![3]),
+        StringType::from_opt_data(vec![Some("c")]),
+    ]))?;
+
+    let output = String::from_utf8(writer.into_inner())?;
+    let expect = r#"{"schema":[{"name":"id","type":"Int32"},{"name":"name","type":"Nullable(String)"}]}
+{"id":1,"name":"a\"b"}
+{"id":2,"name":null}
+{"id":3,"name":"c"}
+"#;
+    assert_eq!(&output, expect);
+
+    Ok(())
+}
@@ -14,13 +14,21 @@
 
 use std::io::Write;
 
+use databend_common_expression::type_check;
 use databend_common_expression::types::*;
 use databend_common_expression::Column;
+use databend_common_expression::ConstantFolder;
+use databend_common_expression::DataBlock;
+use databend_common_expression::Evaluator;
 use databend_common_expression::FromData;
+use databend_common_expression::FunctionContext;
+use databend_common_expression::Scalar;
+use databend_common_functions::BUILTIN_FUNCTIONS;
 use goldenfile::Mint;
 use itertools::Itertools;
 use roaring::RoaringTreemap;
 
+use super::parser;
 use super::run_ast;
 
 #[test]
@@ -45,6 +53,52 @@ fn test_cast() {
     }
 }
 
+#[test]
+fn test_fold_integer_cast_range() {
+    let func_ctx = FunctionContext::default();
+    let block = DataBlock::empty();
+    let evaluator = Evaluator::new(&block, &func_ctx, &BUILTIN_FUNCTIONS);
+    let fold = |text: &str| {
+        let raw_expr = parser::parse_raw_expr(text, &[]);
+        let expr = type_check::check(&raw_expr, &BUILTIN_FUNCTIONS).unwrap();
+        ConstantFolder::fold(&expr, &func_ctx, &BUILTIN_FUNCTIONS).0
+    };
+
+    // In-range narrowings are folded into constants.
+    for (text, expected) in [
+        ("CAST(-128 AS INT8)", NumberScalar::Int8(-128)),
+        ("CAST(255 AS UINT8)", NumberScalar::UInt8(255)),
+        ("CAST(100 AS UINT8)", NumberScalar::UInt8(100)),
+        (
+            "CAST(4294967295 AS INT64)",
+            NumberScalar::Int64(u32::MAX as i64),
+        ),
+    ] {
+        let expr = fold(text);
+        let (_, scalar, _) = expr.as_constant().unwrap();
+        assert_eq!(scalar, &Scalar::Number(expected), "{text}");
+    }
+
+    // Overflowing narrowings are left to the evaluation, which fails instead of
+    // wrapping around.
+    for text in [
+        "CAST(300 AS INT8)",
+        "CAST(-129 AS INT8)",
+        "CAST(256 AS UINT8)",
+        "CAST(-1 AS UINT64)",
+        "CAST(18446744073709551615 AS INT64)",
+    ] {
+        let expr = fold(text);
+        assert!(expr.as_constant().is_none(), "{text}");
+        let err = evaluator.run(&expr).unwrap_err();
+        assert!(err.message().contains("number overflowed"), "{text}");
+    }
+
+    // A branch that is never taken does not fail.
+    let expr = fold("if(false, CAST(300 AS INT8), 0)");
+    assert!(evaluator.run(&expr).is_ok());
+}
+
 fn test_cast_primitive(file: &mut impl Write, is_try: bool) {
     let prefix = if is_try { "TRY_" } else { "" };
 
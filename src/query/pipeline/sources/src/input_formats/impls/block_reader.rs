@@ -0,0 +1,173 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
+use databend_common_expression::ColumnBuilder;
+use databend_common_expression::DataBlock;
+use databend_common_expression::SendableDataBlockStream;
+use databend_common_expression::TableSchemaRef;
+use databend_common_meta_app::principal::OnErrorMode;
+use databend_common_storage::FileParseError;
+use parking_lot::Mutex;
+
+/// A row that failed to parse and was skipped by [`read_csv_blocks`](super::read_csv_blocks),
+/// [`read_ndjson_blocks`](super::read_ndjson_blocks) or
+/// [`read_fixed_width_blocks`](super::read_fixed_width_blocks).
+#[derive(Debug)]
+pub struct RejectedRow {
+    /// 1-based line number in the file where the row starts.
+    pub line: usize,
+    /// The raw bytes of the row, without the record delimiter.
+    pub data: Vec<u8>,
+    pub error: ErrorCode,
+}
+
+pub type RejectedRows = Arc<Mutex<Vec<RejectedRow>>>;
+
+/// A row that failed to parse, see [`RowDecoder::decode_row`].
+pub(crate) struct BadRow {
+    pub error: FileParseError,
+    /// 0-based line number in the file where the row starts.
+    pub line: usize,
+    /// The bytes of the row in the decoded data.
+    pub record: Range<usize>,
+}
+
+/// Decodes the rows of a file that is read into memory, one at a time.
+pub(crate) trait RowDecoder: Send + 'static {
+    /// Push the next row onto `columns`, skipping headers and blank lines.
+    ///
+    /// Returns `Ok(None)` once the data is exhausted. A row that fails to parse may
+    /// leave some of its values pushed, they are dropped by the caller.
+    fn decode_row(
+        &mut self,
+        columns: &mut [ColumnBuilder],
+    ) -> Result<Option<std::result::Result<(), BadRow>>>;
+
+    /// The bytes of a bad row to keep in [`RejectedRow::data`].
+    fn row_data(&self, record: Range<usize>) -> &[u8];
+}
+
+/// Collects the rows of a [`RowDecoder`] into blocks of at most `max_block_rows` rows,
+/// and handles bad rows according to `on_error_mode`.
+pub(crate) struct BlockReader<D> {
+    path: String,
+    schema: TableSchemaRef,
+    decoder: D,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+    rejected_rows: RejectedRows,
+    finished: bool,
+}
+
+impl<D: RowDecoder> BlockReader<D> {
+    pub fn create_stream(
+        path: &str,
+        schema: TableSchemaRef,
+        decoder: D,
+        max_block_rows: usize,
+        on_error_mode: OnErrorMode,
+    ) -> (SendableDataBlockStream, RejectedRows) {
+        let rejected_rows = RejectedRows::default();
+        let reader = BlockReader {
+            path: path.to_string(),
+            schema,
+            decoder,
+            max_block_rows: max_block_rows.max(1),
+            on_error_mode,
+            rejected_rows: rejected_rows.clone(),
+            finished: false,
+        };
+        (Box::pin(futures::stream::iter(reader)), rejected_rows)
+    }
+
+    fn read_block(&mut self) -> Result<Option<DataBlock>> {
+        let mut columns = self
+            .schema
+            .fields()
+            .iter()
+            .map(|f| {
+                ColumnBuilder::with_capacity(&DataType::from(f.data_type()), self.max_block_rows)
+            })
+            .collect::<Vec<_>>();
+        let mut num_rows = 0;
+
+        while num_rows < self.max_block_rows {
+            match self.decoder.decode_row(&mut columns)? {
+                None => break,
+                Some(Ok(())) => num_rows += 1,
+                Some(Err(bad_row)) => {
+                    // drop the values already pushed for the bad row
+                    for column in columns.iter_mut() {
+                        if column.len() > num_rows {
+                            column.pop();
+                        }
+                    }
+                    self.on_error(bad_row)?;
+                }
+            }
+        }
+
+        if num_rows == 0 {
+            return Ok(None);
+        }
+        let columns = columns.into_iter().map(|c| c.build()).collect();
+        Ok(Some(DataBlock::new_from_columns(columns)))
+    }
+
+    fn on_error(&self, bad_row: BadRow) -> Result<()> {
+        let error = bad_row
+            .error
+            .to_error_code(&self.on_error_mode, &self.path, bad_row.line);
+        let mut rejected_rows = self.rejected_rows.lock();
+        if let OnErrorMode::AbortNum(n) = self.on_error_mode {
+            if rejected_rows.len() as u64 + 1 >= n {
+                return Err(error);
+            }
+        }
+
+        rejected_rows.push(RejectedRow {
+            line: bad_row.line + 1,
+            data: self.decoder.row_data(bad_row.record).to_vec(),
+            error,
+        });
+        Ok(())
+    }
+}
+
+impl<D: RowDecoder> Iterator for BlockReader<D> {
+    type Item = Result<DataBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.read_block() {
+            Ok(Some(block)) => Some(Ok(block)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
@@ -13,13 +13,17 @@
 // limitations under the License.
 
 use std::mem;
+use std::ops::Range;
 use std::sync::Arc;
 
+use bstr::ByteSlice;
 use csv_core::ReadRecordResult;
+use databend_common_compress::DecompressDecoder;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::ColumnBuilder;
 use databend_common_expression::Scalar;
+use databend_common_expression::SendableDataBlockStream;
 use databend_common_expression::TableDataType;
 use databend_common_expression::TableSchemaRef;
 use databend_common_formats::FieldDecoder;
@@ -29,17 +33,22 @@ use databend_common_formats::SeparatedTextDecoder;
 use databend_common_meta_app::principal::CsvFileFormatParams;
 use databend_common_meta_app::principal::EmptyFieldAs;
 use databend_common_meta_app::principal::FileFormatParams;
+use databend_common_meta_app::principal::OnErrorMode;
 use databend_common_meta_app::principal::StageFileFormatType;
 use databend_common_storage::FileParseError;
 use databend_common_storage::FileStatus;
 use log::debug;
 
 use crate::input_formats::error_utils::get_decode_error_by_pos;
+use crate::input_formats::impls::block_reader::BadRow;
+use crate::input_formats::impls::block_reader::BlockReader;
+use crate::input_formats::impls::block_reader::RowDecoder;
 use crate::input_formats::AligningStateCommon;
 use crate::input_formats::AligningStateTextBased;
 use crate::input_formats::BlockBuilder;
 use crate::input_formats::InputContext;
 use crate::input_formats::InputFormatTextBase;
+use crate::input_formats::RejectedRows;
 use crate::input_formats::RowBatch;
 use crate::input_formats::SplitInfo;
 
@@ -52,6 +61,23 @@ impl InputFormatCSV {
         Self {}
     }
 
+    fn create_csv_reader(csv_params: &CsvFileFormatParams) -> Result<csv_core::Reader> {
+        let escape = if csv_params.escape.is_empty() {
+            None
+        } else {
+            Some(csv_params.escape.as_bytes()[0])
+        };
+        Ok(csv_core::ReaderBuilder::new()
+            .delimiter(csv_params.field_delimiter.as_bytes()[0])
+            .quote(csv_params.quote.as_bytes()[0])
+            .escape(escape)
+            .terminator(match csv_params.record_delimiter.as_str().try_into()? {
+                RecordDelimiter::Crlf => csv_core::Terminator::CRLF,
+                RecordDelimiter::Any(v) => csv_core::Terminator::Any(v),
+            })
+            .build())
+    }
+
     fn read_column(
         builder: &mut ColumnBuilder,
         field_decoder: &SeparatedTextDecoder,
@@ -108,7 +134,7 @@ impl InputFormatCSV {
             .map_err(|e| get_decode_error_by_pos(column_index, schema, &e.message(), col_data))
     }
 
-    fn read_row(
+    pub(crate) fn read_row(
         field_decoder: &SeparatedTextDecoder,
         buf: &[u8],
         columns: &mut [ColumnBuilder],
@@ -184,20 +210,7 @@ impl InputFormatTextBase for InputFormatCSV {
     ) -> Result<Self::AligningState> {
         let csv_params = CsvFileFormatParams::downcast_unchecked(&ctx.file_format_params);
 
-        let escape = if csv_params.escape.is_empty() {
-            None
-        } else {
-            Some(csv_params.escape.as_bytes()[0])
-        };
-        let reader = csv_core::ReaderBuilder::new()
-            .delimiter(csv_params.field_delimiter.as_bytes()[0])
-            .quote(csv_params.quote.as_bytes()[0])
-            .escape(escape)
-            .terminator(match csv_params.record_delimiter.as_str().try_into()? {
-                RecordDelimiter::Crlf => csv_core::Terminator::CRLF,
-                RecordDelimiter::Any(v) => csv_core::Terminator::Any(v),
-            })
-            .build();
+        let reader = Self::create_csv_reader(csv_params)?;
         let projection = ctx.projection.clone();
         let max_fields = match &projection {
             Some(p) => p.iter().copied().max().unwrap_or(1),
@@ -548,3 +561,207 @@ impl CsvReaderState {
         }
     }
 }
+
+/// Read a local CSV file into a stream of blocks, each with at most `max_block_rows` rows.
+///
+/// The file is parsed with the same delimiter, quote, escape, null token and compression
+/// settings as `COPY INTO`, but without a table context. Rows that fail to parse are
+/// handled according to `on_error_mode`: with `Continue` they are all skipped, with
+/// `AbortNum(n)` the stream fails on the n-th bad row. Skipped rows are collected into
+/// the returned `RejectedRows`, which is complete once the stream is exhausted.
+pub fn read_csv_blocks(
+    path: &str,
+    schema: TableSchemaRef,
+    csv_params: &CsvFileFormatParams,
+    options: &FileFormatOptionsExt,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+) -> Result<(SendableDataBlockStream, RejectedRows)> {
+    let data = std::fs::read(path)
+        .map_err(|e| ErrorCode::StorageOther(format!("fail to read csv file {path}: {e}")))?;
+    read_csv_data(
+        path,
+        data,
+        schema,
+        csv_params,
+        options,
+        max_block_rows,
+        on_error_mode,
+    )
+}
+
+/// Like [`read_csv_blocks`], but parses CSV bytes that are already in memory.
+///
+/// Rejected rows and parse errors refer to the input as `<memory>`. The bytes are only
+/// decompressed if `csv_params` names a compression explicitly.
+pub fn read_csv_blocks_from_bytes(
+    data: Vec<u8>,
+    schema: TableSchemaRef,
+    csv_params: &CsvFileFormatParams,
+    options: &FileFormatOptionsExt,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+) -> Result<(SendableDataBlockStream, RejectedRows)> {
+    read_csv_data(
+        "<memory>",
+        data,
+        schema,
+        csv_params,
+        options,
+        max_block_rows,
+        on_error_mode,
+    )
+}
+
+fn read_csv_data(
+    path: &str,
+    mut data: Vec<u8>,
+    schema: TableSchemaRef,
+    csv_params: &CsvFileFormatParams,
+    options: &FileFormatOptionsExt,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+) -> Result<(SendableDataBlockStream, RejectedRows)> {
+    if let OnErrorMode::SkipFileNum(_) = on_error_mode {
+        return Err(ErrorCode::Unimplemented(
+            "on_error = skip_file is not supported when reading a single csv file",
+        ));
+    }
+    if let Some(algo) = InputContext::get_compression_alg_copy(csv_params.compression, path)? {
+        data = DecompressDecoder::new(algo).decompress_all(&data)?;
+    }
+
+    let decoder = CsvRowDecoder {
+        reader: InputFormatCSV::create_csv_reader(csv_params)?,
+        field_decoder: SeparatedTextDecoder::create_csv(csv_params, options, true),
+        empty_field_as: csv_params.empty_field_as.clone(),
+        error_on_column_count_mismatch: csv_params.error_on_column_count_mismatch,
+        rows_to_skip: csv_params.headers as usize,
+        output: vec![0u8; data.len()],
+        field_ends: vec![0; schema.num_fields() + MAX_CSV_COLUMNS],
+        schema: schema.clone(),
+        data,
+        pos: 0,
+        lines: 0,
+        record_start: 0,
+    };
+    Ok(BlockReader::create_stream(
+        path,
+        schema,
+        decoder,
+        max_block_rows,
+        on_error_mode,
+    ))
+}
+
+struct CsvRowDecoder {
+    reader: csv_core::Reader,
+    field_decoder: SeparatedTextDecoder,
+    empty_field_as: EmptyFieldAs,
+    error_on_column_count_mismatch: bool,
+    schema: TableSchemaRef,
+
+    data: Vec<u8>,
+    // offset of the first unread byte in `data`
+    pos: usize,
+    // number of lines read so far, including the skipped headers; a record
+    // spans several lines if a quoted field contains line breaks
+    lines: usize,
+    // offset in `data` of the record being read
+    record_start: usize,
+    rows_to_skip: usize,
+    output: Vec<u8>,
+    field_ends: Vec<usize>,
+}
+
+impl RowDecoder for CsvRowDecoder {
+    fn decode_row(
+        &mut self,
+        columns: &mut [ColumnBuilder],
+    ) -> Result<Option<std::result::Result<(), BadRow>>> {
+        let num_fields = self.schema.num_fields();
+        // output bytes and field ends of the record being read
+        let mut n_out = 0;
+        let mut n_end = 0;
+
+        loop {
+            let (result, n_in, out, end) = self.reader.read_record(
+                &self.data[self.pos..],
+                &mut self.output[n_out..],
+                &mut self.field_ends[n_end..],
+            );
+            self.pos += n_in;
+            n_out += out;
+            n_end += end;
+            match result {
+                // The last record has no terminator, call again with an empty input to finish it.
+                ReadRecordResult::InputEmpty => continue,
+                ReadRecordResult::OutputFull => {
+                    return Err(ErrorCode::BadBytes(
+                        "Bug: CSV Reader return output longer then input.",
+                    ));
+                }
+                ReadRecordResult::OutputEndsFull => {
+                    return Err(ErrorCode::BadBytes(format!(
+                        "too many fields, expect {}, got more than {}",
+                        num_fields,
+                        self.field_ends.len()
+                    )));
+                }
+                ReadRecordResult::End => return Ok(None),
+                ReadRecordResult::Record => {
+                    let line = self.lines;
+                    let num_ends = std::mem::take(&mut n_end);
+                    let record = self.record_start..self.pos;
+                    self.record_start = self.pos;
+                    n_out = 0;
+                    self.lines += self.data[record.clone()]
+                        .iter()
+                        .filter(|c| **c == b'\n')
+                        .count()
+                        .max(1);
+                    if self.rows_to_skip > 0 {
+                        self.rows_to_skip -= 1;
+                        continue;
+                    }
+
+                    let mut n_end = num_ends;
+                    if n_end < num_fields && !self.error_on_column_count_mismatch && n_end > 0 {
+                        // pretend the missing trailing fields are empty
+                        let end = self.field_ends[n_end - 1];
+                        self.field_ends[n_end..num_fields].fill(end);
+                        n_end = num_fields;
+                    }
+                    let result = if n_end < num_fields
+                        || (n_end > num_fields && self.error_on_column_count_mismatch)
+                    {
+                        Err(FileParseError::NumberOfColumnsMismatch {
+                            table: num_fields,
+                            file: n_end,
+                        })
+                    } else {
+                        InputFormatCSV::read_row(
+                            &self.field_decoder,
+                            &self.output,
+                            columns,
+                            &self.schema,
+                            &self.field_ends[..num_fields],
+                            &None,
+                            &None,
+                            &self.empty_field_as,
+                        )
+                    };
+                    return Ok(Some(result.map_err(|error| BadRow {
+                        error,
+                        line,
+                        record,
+                    })));
+                }
+            }
+        }
+    }
+
+    fn row_data(&self, record: Range<usize>) -> &[u8] {
+        self.data[record].trim_end_with(|c| c == '\r' || c == '\n')
+    }
+}
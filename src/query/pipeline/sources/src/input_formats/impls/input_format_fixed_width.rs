@@ -0,0 +1,180 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::ops::Range;
+
+use bstr::ByteSlice;
+use databend_common_compress::DecompressDecoder;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::ColumnBuilder;
+use databend_common_expression::SendableDataBlockStream;
+use databend_common_expression::TableSchemaRef;
+use databend_common_formats::FileFormatOptionsExt;
+use databend_common_formats::RecordDelimiter;
+use databend_common_formats::SeparatedTextDecoder;
+use databend_common_meta_app::principal::CsvFileFormatParams;
+use databend_common_meta_app::principal::EmptyFieldAs;
+use databend_common_meta_app::principal::OnErrorMode;
+
+use crate::input_formats::impls::block_reader::BadRow;
+use crate::input_formats::impls::block_reader::BlockReader;
+use crate::input_formats::impls::block_reader::RowDecoder;
+use crate::input_formats::InputContext;
+use crate::input_formats::InputFormatCSV;
+use crate::input_formats::RejectedRows;
+
+/// Read a local fixed-width text file into a stream of blocks, each with at most
+/// `max_block_rows` rows.
+///
+/// Each line holds one row, and the i-th column takes the next `widths[i]` bytes of it.
+/// The spaces padding a field on either side are trimmed, then the field is decoded like
+/// a CSV field: `csv_params` supplies the null token, `empty_field_as`, the record
+/// delimiter, the number of header lines and the compression. A line shorter than the
+/// sum of the widths has its missing fields empty, bytes past the last column are ignored
+/// and blank lines are skipped. Bad rows are handled like in
+/// [`read_csv_blocks`](super::read_csv_blocks).
+pub fn read_fixed_width_blocks(
+    path: &str,
+    schema: TableSchemaRef,
+    widths: &[usize],
+    csv_params: &CsvFileFormatParams,
+    options: &FileFormatOptionsExt,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+) -> Result<(SendableDataBlockStream, RejectedRows)> {
+    if widths.len() != schema.num_fields() {
+        return Err(ErrorCode::BadArguments(format!(
+            "expect {} column widths, got {}",
+            schema.num_fields(),
+            widths.len()
+        )));
+    }
+    if widths.iter().any(|w| *w == 0) {
+        return Err(ErrorCode::BadArguments("column widths must be positive"));
+    }
+    if let OnErrorMode::SkipFileNum(_) = on_error_mode {
+        return Err(ErrorCode::Unimplemented(
+            "on_error = skip_file is not supported when reading a single fixed-width file",
+        ));
+    }
+
+    let mut data = std::fs::read(path).map_err(|e| {
+        ErrorCode::StorageOther(format!("fail to read fixed-width file {path}: {e}"))
+    })?;
+    if let Some(algo) = InputContext::get_compression_alg_copy(csv_params.compression, path)? {
+        data = DecompressDecoder::new(algo).decompress_all(&data)?;
+    }
+
+    let decoder = FixedWidthRowDecoder {
+        widths: widths.to_vec(),
+        field_decoder: SeparatedTextDecoder::create_csv(csv_params, options, true),
+        empty_field_as: csv_params.empty_field_as.clone(),
+        record_delimiter: csv_params.record_delimiter.as_str().try_into()?,
+        schema: schema.clone(),
+        data,
+        pos: 0,
+        lines: 0,
+        rows_to_skip: csv_params.headers as usize,
+        output: vec![],
+        field_ends: vec![0; widths.len()],
+    };
+    Ok(BlockReader::create_stream(
+        path,
+        schema,
+        decoder,
+        max_block_rows,
+        on_error_mode,
+    ))
+}
+
+struct FixedWidthRowDecoder {
+    widths: Vec<usize>,
+    field_decoder: SeparatedTextDecoder,
+    empty_field_as: EmptyFieldAs,
+    record_delimiter: RecordDelimiter,
+    schema: TableSchemaRef,
+
+    data: Vec<u8>,
+    // offset of the first unread byte in `data`
+    pos: usize,
+    // number of lines read so far, including the skipped headers
+    lines: usize,
+    rows_to_skip: usize,
+    // the trimmed fields of the row being read
+    output: Vec<u8>,
+    field_ends: Vec<usize>,
+}
+
+impl RowDecoder for FixedWidthRowDecoder {
+    fn decode_row(
+        &mut self,
+        columns: &mut [ColumnBuilder],
+    ) -> Result<Option<std::result::Result<(), BadRow>>> {
+        while self.pos < self.data.len() {
+            let end = self.data[self.pos..]
+                .find_byte(self.record_delimiter.end())
+                .map_or(self.data.len(), |i| self.pos + i);
+            let mut record = self.pos..end;
+            let line = self.lines;
+            self.pos = end + 1;
+            self.lines += 1;
+            if let RecordDelimiter::Crlf = self.record_delimiter {
+                if self.data[record.clone()].ends_with(b"\r") {
+                    record.end -= 1;
+                }
+            }
+
+            if self.rows_to_skip > 0 {
+                self.rows_to_skip -= 1;
+                continue;
+            }
+            if self.data[record.clone()].trim().is_empty() {
+                continue;
+            }
+
+            self.output.clear();
+            let mut field_start = record.start;
+            for (i, width) in self.widths.iter().enumerate() {
+                let start = field_start.min(record.end);
+                let end = (field_start + width).min(record.end);
+                self.output
+                    .extend_from_slice(self.data[start..end].trim_with(|c| c == ' '));
+                self.field_ends[i] = self.output.len();
+                field_start += width;
+            }
+
+            let result = InputFormatCSV::read_row(
+                &self.field_decoder,
+                &self.output,
+                columns,
+                &self.schema,
+                &self.field_ends,
+                &None,
+                &None,
+                &self.empty_field_as,
+            );
+            return Ok(Some(result.map_err(|error| BadRow {
+                error,
+                line,
+                record,
+            })));
+        }
+        Ok(None)
+    }
+
+    fn row_data(&self, record: Range<usize>) -> &[u8] {
+        &self.data[record]
+    }
+}
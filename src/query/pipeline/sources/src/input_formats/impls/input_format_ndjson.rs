@@ -12,26 +12,36 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::ops::Range;
 use std::sync::Arc;
 
 use bstr::ByteSlice;
+use databend_common_compress::DecompressDecoder;
+use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::ColumnBuilder;
 use databend_common_expression::Scalar;
+use databend_common_expression::SendableDataBlockStream;
 use databend_common_expression::TableSchemaRef;
 use databend_common_formats::FieldDecoder;
 use databend_common_formats::FieldJsonAstDecoder;
 use databend_common_formats::FileFormatOptionsExt;
 use databend_common_meta_app::principal::FileFormatParams;
+use databend_common_meta_app::principal::NdJsonFileFormatParams;
 use databend_common_meta_app::principal::NullAs;
+use databend_common_meta_app::principal::OnErrorMode;
 use databend_common_meta_app::principal::StageFileFormatType;
 use databend_common_storage::FileParseError;
 
 use crate::input_formats::error_utils::truncate_column_data;
+use crate::input_formats::impls::block_reader::BadRow;
+use crate::input_formats::impls::block_reader::BlockReader;
+use crate::input_formats::impls::block_reader::RowDecoder;
 use crate::input_formats::AligningStateRowDelimiter;
 use crate::input_formats::BlockBuilder;
 use crate::input_formats::InputContext;
 use crate::input_formats::InputFormatTextBase;
+use crate::input_formats::RejectedRows;
 use crate::input_formats::RowBatch;
 use crate::input_formats::SplitInfo;
 
@@ -218,3 +228,103 @@ impl InputFormatTextBase for InputFormatNDJson {
         Ok(())
     }
 }
+
+/// Read a local NDJSON file into a stream of blocks, each with at most `max_block_rows` rows.
+///
+/// Every non-empty line holds one JSON object whose keys are matched against the schema
+/// fields, nested objects and arrays can be read into `Variant`, `Map` and `Array` columns.
+/// Missing keys and JSON nulls are handled by `missing_field_as` and `null_field_as`,
+/// bad rows by `on_error_mode`, the same way as [`read_csv_blocks`](super::read_csv_blocks).
+pub fn read_ndjson_blocks(
+    path: &str,
+    schema: TableSchemaRef,
+    params: &NdJsonFileFormatParams,
+    options: &FileFormatOptionsExt,
+    max_block_rows: usize,
+    on_error_mode: OnErrorMode,
+) -> Result<(SendableDataBlockStream, RejectedRows)> {
+    if let OnErrorMode::SkipFileNum(_) = on_error_mode {
+        return Err(ErrorCode::Unimplemented(
+            "on_error = skip_file is not supported when reading a single ndjson file",
+        ));
+    }
+    if let NullAs::Error = params.null_field_as {
+        return Err(ErrorCode::BadArguments("NULL_FIELD_AS cannot be ERROR"));
+    }
+    let mut data = std::fs::read(path)
+        .map_err(|e| ErrorCode::StorageOther(format!("fail to read ndjson file {path}: {e}")))?;
+    if let Some(algo) = InputContext::get_compression_alg_copy(params.compression, path)? {
+        data = DecompressDecoder::new(algo).decompress_all(&data)?;
+    }
+
+    let decoder = NdJsonRowDecoder {
+        field_decoder: FieldJsonAstDecoder::create(options, true),
+        schema: schema.clone(),
+        data,
+        pos: 0,
+        lines: 0,
+        null_field_as: params.null_field_as.clone(),
+        missing_field_as: params.missing_field_as.clone(),
+    };
+    Ok(BlockReader::create_stream(
+        path,
+        schema,
+        decoder,
+        max_block_rows,
+        on_error_mode,
+    ))
+}
+
+struct NdJsonRowDecoder {
+    field_decoder: FieldJsonAstDecoder,
+    schema: TableSchemaRef,
+
+    data: Vec<u8>,
+    // offset of the first unread byte in `data`
+    pos: usize,
+    // number of lines read so far
+    lines: usize,
+    null_field_as: NullAs,
+    missing_field_as: NullAs,
+}
+
+impl RowDecoder for NdJsonRowDecoder {
+    fn decode_row(
+        &mut self,
+        columns: &mut [ColumnBuilder],
+    ) -> Result<Option<std::result::Result<(), BadRow>>> {
+        while self.pos < self.data.len() {
+            let end = self.data[self.pos..]
+                .find_byte(b'\n')
+                .map_or(self.data.len(), |i| self.pos + i);
+            let record = self.pos..end;
+            let line = self.lines;
+            self.pos = end + 1;
+            self.lines += 1;
+
+            let buf = self.data[record.clone()].trim();
+            if buf.is_empty() {
+                continue;
+            }
+            let result = InputFormatNDJson::read_row(
+                &self.field_decoder,
+                buf,
+                columns,
+                &self.schema,
+                &None,
+                &self.null_field_as,
+                &self.missing_field_as,
+            );
+            return Ok(Some(result.map_err(|error| BadRow {
+                error,
+                line,
+                record,
+            })));
+        }
+        Ok(None)
+    }
+
+    fn row_data(&self, record: Range<usize>) -> &[u8] {
+        self.data[record].trim_end_with(|c| c == '\r')
+    }
+}
@@ -16,6 +16,7 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fmt::Formatter;
+use std::fs::File;
 use std::io::Cursor;
 use std::io::Read;
 use std::io::Seek;
@@ -37,6 +38,7 @@ use databend_common_expression::BlockMetaInfo;
 use databend_common_expression::DataBlock;
 use databend_common_expression::DataField;
 use databend_common_expression::DataSchema;
+use databend_common_expression::SendableDataBlockStream;
 use databend_common_expression::TableSchemaRef;
 use databend_common_meta_app::principal::StageInfo;
 use databend_common_pipeline_core::Pipeline;
@@ -399,6 +401,60 @@ impl AligningStateTrait for ParquetAligningState {
     }
 }
 
+/// Read a local parquet file into a stream of blocks, one block per row group.
+///
+/// Only the columns named in `schema` are read (matched case-insensitively, in the order
+/// of `schema`); the column chunks of the other columns are never loaded. The data types
+/// of the blocks are the ones stored in the file.
+pub fn read_parquet_blocks(path: &str, schema: TableSchemaRef) -> Result<SendableDataBlockStream> {
+    let mut file = File::open(path)
+        .map_err(|e| ErrorCode::StorageOther(format!("fail to open parquet file {path}: {e}")))?;
+    let file_meta = read_metadata(&mut file)?;
+    let infer_schema = infer_schema_with_extension(&file_meta)?;
+    let fields = get_used_fields(&infer_schema.fields, &schema)?;
+    let data_schema = DataSchema::new(
+        fields
+            .iter()
+            .map(DataField::try_from)
+            .collect::<Result<Vec<_>>>()?,
+    );
+
+    let reader = ParquetBlockReader {
+        path: path.to_string(),
+        file,
+        row_groups: file_meta.row_groups.into_iter(),
+        fields: Arc::new(fields),
+        schema: data_schema,
+    };
+    Ok(Box::pin(futures::stream::iter(reader)))
+}
+
+struct ParquetBlockReader {
+    path: String,
+    file: File,
+    row_groups: std::vec::IntoIter<RowGroupMetaData>,
+    fields: Arc<Vec<Field>>,
+    schema: DataSchema,
+}
+
+impl ParquetBlockReader {
+    fn read_row_group(&mut self, meta: RowGroupMetaData) -> Result<DataBlock> {
+        let mut row_group =
+            RowGroupInMemory::read(self.path.clone(), &mut self.file, meta, self.fields.clone())?;
+        let chunk = row_group.get_arrow_chunk()?;
+        DataBlock::from_arrow_chunk(&chunk, &self.schema)
+    }
+}
+
+impl Iterator for ParquetBlockReader {
+    type Item = Result<DataBlock>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let meta = self.row_groups.next()?;
+        Some(self.read_row_group(meta))
+    }
+}
+
 fn get_used_fields(fields: &Vec<Field>, schema: &TableSchemaRef) -> Result<Vec<Field>> {
     let mut read_fields = Vec::with_capacity(fields.len());
     for f in schema.fields().iter() {
@@ -12,14 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod block_reader;
 mod input_format_csv;
+mod input_format_fixed_width;
 mod input_format_ndjson;
 mod input_format_parquet;
 mod input_format_tsv;
 mod input_format_xml;
 
+pub use block_reader::RejectedRow;
+pub use block_reader::RejectedRows;
+pub use input_format_csv::read_csv_blocks;
+pub use input_format_csv::read_csv_blocks_from_bytes;
 pub use input_format_csv::InputFormatCSV;
+pub use input_format_fixed_width::read_fixed_width_blocks;
+pub use input_format_ndjson::read_ndjson_blocks;
 pub use input_format_ndjson::InputFormatNDJson;
+pub use input_format_parquet::read_parquet_blocks;
 pub use input_format_parquet::InputFormatParquet;
 pub use input_format_tsv::InputFormatTSV;
 pub use input_format_xml::InputFormatXML;
@@ -560,6 +560,10 @@ pub struct BlockBuilder<T> {
     pub projection: Option<Vec<usize>>,
     pub file_status: FileStatus,
     pub ident_case_sensitive: bool,
+    // rows per output block, the rows that don't fill a block are kept
+    // until more rows arrive or the input ends.
+    block_rows: Option<usize>,
+    remain: Option<DataBlock>,
     phantom: PhantomData<T>,
 }
 
@@ -590,6 +594,10 @@ impl<T: InputFormatTextBase> BlockBuilder<T> {
             rounding_mode,
         );
         let projection = ctx.projection.clone();
+        let block_rows = match ctx.settings.get_input_block_rows().unwrap_or(0) {
+            0 => None,
+            n => Some(n as usize),
+        };
 
         BlockBuilder {
             ident_case_sensitive: ctx.file_format_options_ext.ident_case_sensitive,
@@ -599,11 +607,34 @@ impl<T: InputFormatTextBase> BlockBuilder<T> {
             phantom: PhantomData,
             projection,
             file_status: Default::default(),
+            block_rows,
+            remain: None,
             ctx,
         }
     }
 
-    fn flush(&mut self) -> Result<Vec<DataBlock>> {
+    fn flush(&mut self, is_end: bool) -> Result<Vec<DataBlock>> {
+        let mut blocks = self.build()?;
+        let Some(block_rows) = self.block_rows else {
+            return Ok(blocks);
+        };
+
+        if let Some(remain) = self.remain.take() {
+            blocks.insert(0, remain);
+        }
+        if blocks.is_empty() {
+            return Ok(vec![]);
+        }
+        let block = DataBlock::concat(&blocks)?;
+        let (mut blocks, remain) = block.split_by_rows(block_rows);
+        match remain {
+            Some(remain) if is_end => blocks.push(remain),
+            remain => self.remain = remain,
+        }
+        Ok(blocks)
+    }
+
+    fn build(&mut self) -> Result<Vec<DataBlock>> {
         let columns: Vec<Column> = self
             .mutable_columns
             .iter_mut()
@@ -668,13 +699,14 @@ impl<T: InputFormatTextBase> BlockBuilderTrait for BlockBuilder<T> {
             );
             if self.num_rows >= self.ctx.block_compact_thresholds.min_rows_per_block
                 || mem > self.ctx.block_compact_thresholds.max_bytes_per_block
+                || matches!(self.block_rows, Some(n) if self.num_rows >= n)
             {
-                self.flush()
+                self.flush(false)
             } else {
                 Ok(vec![])
             }
         } else {
-            self.flush()
+            self.flush(true)
         }
     }
 }
@@ -24,11 +24,20 @@ mod source_aligner;
 mod transform_deserializer;
 
 pub use beyond_end_reader::BeyondEndReader;
+pub use impls::read_csv_blocks;
+pub use impls::read_csv_blocks_from_bytes;
+pub use impls::read_fixed_width_blocks;
+pub use impls::read_ndjson_blocks;
+pub use impls::read_parquet_blocks;
+pub use impls::InputFormatCSV;
+pub use impls::RejectedRow;
+pub use impls::RejectedRows;
 pub use input_context::InputContext;
 pub use input_context::InputPlan;
 pub use input_context::StreamPlan;
 pub use input_format::InputFormat;
 pub use input_format_text::*;
+pub use input_pipeline::BlockBuilderTrait;
 pub use input_pipeline::StreamingReadBatch;
 pub use input_split::split_by_size;
 pub use input_split::SplitInfo;
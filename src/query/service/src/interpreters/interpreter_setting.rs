@@ -17,6 +17,7 @@ use std::sync::Arc;
 use chrono_tz::Tz;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
+use databend_common_io::prelude::FloatFormat;
 use databend_common_sql::plans::SettingPlan;
 
 use crate::interpreters::Interpreter;
@@ -49,6 +50,10 @@ impl Interpreter for SettingInterpreter {
         let mut values: Vec<String> = vec![];
         let mut is_globals: Vec<bool> = vec![];
         for var in plan.vars {
+            if var.variable.eq_ignore_ascii_case("float_format") {
+                // check if the float format is valid, it is only parsed when a query runs
+                var.value.parse::<FloatFormat>()?;
+            }
             let ok = match var.variable.to_lowercase().as_str() {
                 // To be compatible with some drivers
                 "sql_mode" | "autocommit" => false,
@@ -162,7 +162,10 @@ impl HashJoinState {
                             valids = or_validities(valids, Some(bitmap.clone()));
                         }
                     }
-                    Column::Null { .. } => {}
+                    Column::Null { .. } => {
+                        // A NULL key never matches, so its marker is always NULL.
+                        valids = or_validities(valids, Some(Bitmap::new_constant(false, num_rows)));
+                    }
                     _c => {
                         valids = Some(Bitmap::new_constant(true, num_rows));
                         break;
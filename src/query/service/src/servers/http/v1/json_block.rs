@@ -47,7 +47,7 @@ pub fn block_to_json_value(
         .collect();
 
     let mut res = Vec::new();
-    let encoder = FieldEncoderValues::create_for_http_handler(format.timezone);
+    let encoder = FieldEncoderValues::create_for_http_handler(format);
     let mut buf = vec![];
     for row_index in 0..rows_size {
         let mut row: Vec<JsonValue> = Vec::with_capacity(block.num_columns());
@@ -203,7 +203,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for InteractiveWorke
                 ));
             }
 
-            let mut writer = DFQueryResultWriter::create(writer);
+            let mut writer = DFQueryResultWriter::create(writer, None);
 
             let instant = Instant::now();
             let query_result = self
@@ -367,6 +367,10 @@ impl InteractiveWorkerBase {
                             Self::exec_query(interpreter.clone(), &context).await?;
                         let schema = plan.schema();
                         let format = context.get_format_settings()?;
+                        let max_rows = match context.get_settings().get_mysql_max_result_rows()? {
+                            0 => None,
+                            n => Some(n as usize),
+                        };
                         Ok((
                             QueryResult::create(
                                 blocks,
@@ -374,7 +378,8 @@ impl InteractiveWorkerBase {
                                 has_result_set,
                                 schema,
                                 query.to_string(),
-                            ),
+                            )
+                            .with_max_rows(max_rows),
                             Some(format),
                         ))
                     }
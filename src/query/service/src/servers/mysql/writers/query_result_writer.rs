@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use databend_common_base::base::tokio::io::AsyncWrite;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
@@ -23,6 +25,7 @@ use databend_common_expression::DataField;
 use databend_common_expression::DataSchemaRef;
 use databend_common_expression::ScalarRef;
 use databend_common_expression::SendableDataBlockStream;
+use databend_common_formats::field_encoder::FieldEncoderJSON;
 use databend_common_formats::field_encoder::FieldEncoderValues;
 use databend_common_io::prelude::FormatSettings;
 use futures_util::StreamExt;
@@ -45,6 +48,7 @@ pub struct QueryResult {
     has_result_set: bool,
     schema: DataSchemaRef,
     sql: String,
+    max_rows: Option<usize>,
 }
 
 impl QueryResult {
@@ -61,12 +65,86 @@ impl QueryResult {
             has_result_set,
             schema,
             sql,
+            max_rows: None,
         }
     }
+
+    /// Stop streaming after `max_rows` rows; a warning is appended to the OK packet info.
+    pub fn with_max_rows(mut self, max_rows: Option<usize>) -> QueryResult {
+        self.max_rows = max_rows;
+        self
+    }
 }
 
+/// Remaps the column type sent to the client for a field, e.g. to report counts
+/// as `MYSQL_TYPE_LONG` for clients that mishandle `MYSQL_TYPE_LONGLONG`.
+pub type ColumnTypeOverride = Arc<dyn Fn(&DataField, ColumnType) -> ColumnType + Send + Sync>;
+
 pub struct DFQueryResultWriter<'a, W: AsyncWrite + Send + Unpin> {
     inner: Option<QueryResultWriter<'a, W>>,
+    column_type_override: Option<ColumnTypeOverride>,
+}
+
+fn convert_field_type(field: &DataField) -> Result<ColumnType> {
+    match field.data_type().remove_nullable() {
+        DataType::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
+        DataType::EmptyArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::EmptyMap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
+        DataType::Binary => Ok(ColumnType::MYSQL_TYPE_BLOB),
+        DataType::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Number(num_ty) => match num_ty {
+            NumberDataType::Int8 => Ok(ColumnType::MYSQL_TYPE_TINY),
+            NumberDataType::Int16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
+            NumberDataType::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+            NumberDataType::Int64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
+            NumberDataType::UInt8 => Ok(ColumnType::MYSQL_TYPE_TINY),
+            NumberDataType::UInt16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
+            NumberDataType::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
+            NumberDataType::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
+            NumberDataType::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+            NumberDataType::Float64 => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
+        },
+        DataType::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
+        DataType::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
+        DataType::Array(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Map(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Bitmap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Tuple(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
+        DataType::Decimal(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
+        _ => Err(ErrorCode::Unimplemented(format!(
+            "Unsupported column type:{:?}",
+            field.data_type()
+        ))),
+    }
+}
+
+fn make_column_from_field(
+    field: &DataField,
+    column_type_override: Option<&ColumnTypeOverride>,
+) -> Result<Column> {
+    let mut column_type = convert_field_type(field)?;
+    if let Some(column_type_override) = column_type_override {
+        column_type = column_type_override(field, column_type);
+    }
+    Ok(Column {
+        table: "".to_string(),
+        column: field.name().to_string(),
+        coltype: column_type,
+        colflags: ColumnFlags::empty(),
+    })
+}
+
+fn convert_schema(
+    schema: &DataSchemaRef,
+    column_type_override: Option<&ColumnTypeOverride>,
+) -> Result<Vec<Column>> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| make_column_from_field(field, column_type_override))
+        .collect()
 }
 
 fn write_field<W: AsyncWrite + Unpin>(
@@ -75,45 +153,94 @@ fn write_field<W: AsyncWrite + Unpin>(
     encoder: &FieldEncoderValues,
     buf: &mut Vec<u8>,
     row_index: usize,
+    quote: bool,
 ) -> Result<()> {
     buf.clear();
-    encoder.write_field(column, row_index, buf, false);
+    encoder.write_field(column, row_index, buf, quote);
     row_writer.write_col(&buf[..])?;
     Ok(())
 }
 
 impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
-    pub fn create(inner: QueryResultWriter<'a, W>) -> DFQueryResultWriter<'a, W> {
-        DFQueryResultWriter::<'a, W> { inner: Some(inner) }
+    /// Create a writer for `inner`. `column_type_override` remaps the column types sent
+    /// to the client, `None` sends the types given by the schema.
+    pub fn create(
+        inner: QueryResultWriter<'a, W>,
+        column_type_override: Option<ColumnTypeOverride>,
+    ) -> DFQueryResultWriter<'a, W> {
+        DFQueryResultWriter::<'a, W> {
+            inner: Some(inner),
+            column_type_override,
+        }
     }
 
+    /// Write the result of one statement to the client.
+    ///
+    /// The `FormatSettings` returned with the result wins over `format`. The former is built
+    /// by the query context from the session settings, so a `SET timezone = ..` of the session
+    /// applies to dates and timestamps. `format` is the session default, used for results
+    /// produced without a query context such as federated queries.
     #[async_backtrace::framed]
     pub async fn write(
         &mut self,
         query_result: Result<(QueryResult, Option<FormatSettings>)>,
         format: &FormatSettings,
     ) -> Result<()> {
-        if let Some(writer) = self.inner.take() {
+        self.write_many(vec![query_result], format).await
+    }
+
+    /// Write the result sets of several statements to the client. All but the last
+    /// result set are sent with the `SERVER_MORE_RESULTS_EXISTS` flag. An error, either
+    /// an `Err` in `query_results` or one raised while streaming a result set, is sent
+    /// to the client and ends the sequence: the remaining results are not written.
+    ///
+    /// The MySQL handler does not split multi-statement packets yet, it only writes a
+    /// single result through [`Self::write`].
+    #[async_backtrace::framed]
+    pub async fn write_many(
+        &mut self,
+        query_results: Vec<Result<(QueryResult, Option<FormatSettings>)>>,
+        format: &FormatSettings,
+    ) -> Result<()> {
+        let num_results = query_results.len();
+        for (index, query_result) in query_results.into_iter().enumerate() {
+            let Some(writer) = self.inner.take() else {
+                break;
+            };
             match query_result {
                 Ok((query_result, query_format)) => {
-                    if let Some(format) = query_format {
-                        Self::ok(query_result, writer, &format).await?
-                    } else {
-                        Self::ok(query_result, writer, format).await?
-                    }
+                    let column_type_override = self.column_type_override.as_ref();
+                    let format = query_format.as_ref().unwrap_or(format);
+                    let more_results = index + 1 < num_results;
+                    self.inner = Self::ok(
+                        query_result,
+                        writer,
+                        format,
+                        column_type_override,
+                        more_results,
+                    )
+                    .await?;
+                }
+                Err(error) => {
+                    Self::err(&error, writer).await?;
+                    break;
                 }
-                Err(error) => Self::err(&error, writer).await?,
             }
         }
         Ok(())
     }
 
+    /// Write one result set. With `more_results` the result set is ended with the
+    /// `SERVER_MORE_RESULTS_EXISTS` flag and the writer is handed back for the next one.
+    /// `None` is returned if an error was sent to the client instead.
     #[async_backtrace::framed]
     async fn ok(
         mut query_result: QueryResult,
         dataset_writer: QueryResultWriter<'a, W>,
         format: &FormatSettings,
-    ) -> Result<()> {
+        column_type_override: Option<&ColumnTypeOverride>,
+        more_results: bool,
+    ) -> Result<Option<QueryResultWriter<'a, W>>> {
         // XXX: num_columns == 0 may is error?
         if !query_result.has_result_set {
             // For statements without result sets, we still need to pull the stream because errors may occur in the stream.
@@ -132,7 +259,7 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                         )
                         .await?;
 
-                    return Ok(());
+                    return Ok(None);
                 }
             }
 
@@ -140,71 +267,48 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                 .extra_info
                 .map(|r| r.affected_rows())
                 .unwrap_or_default();
-            dataset_writer
-                .completed(OkResponse {
-                    affected_rows,
-                    ..Default::default()
-                })
-                .await?;
-            return Ok(());
-        }
-
-        fn convert_field_type(field: &DataField) -> Result<ColumnType> {
-            match field.data_type().remove_nullable() {
-                DataType::Null => Ok(ColumnType::MYSQL_TYPE_NULL),
-                DataType::EmptyArray => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::EmptyMap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Boolean => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                DataType::Binary => Ok(ColumnType::MYSQL_TYPE_BLOB),
-                DataType::String => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Number(num_ty) => match num_ty {
-                    NumberDataType::Int8 => Ok(ColumnType::MYSQL_TYPE_TINY),
-                    NumberDataType::Int16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                    NumberDataType::Int32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                    NumberDataType::Int64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
-                    NumberDataType::UInt8 => Ok(ColumnType::MYSQL_TYPE_TINY),
-                    NumberDataType::UInt16 => Ok(ColumnType::MYSQL_TYPE_SHORT),
-                    NumberDataType::UInt32 => Ok(ColumnType::MYSQL_TYPE_LONG),
-                    NumberDataType::UInt64 => Ok(ColumnType::MYSQL_TYPE_LONGLONG),
-                    NumberDataType::Float32 => Ok(ColumnType::MYSQL_TYPE_FLOAT),
-                    NumberDataType::Float64 => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
-                },
-                DataType::Date => Ok(ColumnType::MYSQL_TYPE_DATE),
-                DataType::Timestamp => Ok(ColumnType::MYSQL_TYPE_DATETIME),
-                DataType::Array(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Map(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Bitmap => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Tuple(_) => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Variant => Ok(ColumnType::MYSQL_TYPE_VARCHAR),
-                DataType::Decimal(_) => Ok(ColumnType::MYSQL_TYPE_DECIMAL),
-                _ => Err(ErrorCode::Unimplemented(format!(
-                    "Unsupported column type:{:?}",
-                    field.data_type()
-                ))),
+            let ok_response = OkResponse {
+                affected_rows,
+                ..Default::default()
+            };
+            if more_results {
+                return Ok(Some(dataset_writer.complete_one(ok_response).await?));
             }
+            dataset_writer.completed(ok_response).await?;
+            return Ok(None);
         }
 
-        fn make_column_from_field(field: &DataField) -> Result<Column> {
-            convert_field_type(field).map(|column_type| Column {
-                table: "".to_string(),
-                column: field.name().to_string(),
-                coltype: column_type,
-                colflags: ColumnFlags::empty(),
-            })
-        }
-
-        fn convert_schema(schema: &DataSchemaRef) -> Result<Vec<Column>> {
-            schema.fields().iter().map(make_column_from_field).collect()
-        }
-
-        let _tz = format.timezone;
-        match convert_schema(&query_result.schema) {
-            Err(error) => Self::err(&error, dataset_writer).await,
+        match convert_schema(&query_result.schema, column_type_override) {
+            Err(error) => {
+                Self::err(&error, dataset_writer).await?;
+                Ok(None)
+            }
             Ok(columns) => {
                 let mut row_writer = dataset_writer.start(&columns).await?;
                 let blocks = &mut query_result.blocks;
+                let max_rows = query_result.max_rows;
+                let mut written_rows = 0;
+                let mut truncated = false;
+
+                // The encoders and the scratch buffer live for the whole result. `buf` is
+                // cleared before each text field is encoded and only borrowed until the
+                // field is handed to `write_col`, so its capacity is reused across rows.
+                // `format` is already resolved by the caller, its time zone is the one of the
+                // session when the result came with query settings, see `write`.
+                let encoder = FieldEncoderValues::create_for_mysql_handler(format);
+                let json_encoder = FieldEncoderJSON::create_for_mysql_handler(format.timezone);
+                let mut buf = Vec::<u8>::new();
 
                 while let Some(block) = blocks.next().await {
+                    if matches!(max_rows, Some(max_rows) if written_rows >= max_rows) {
+                        // An empty block drops no rows, the result may still be complete.
+                        if matches!(&block, Ok(block) if block.num_rows() == 0) {
+                            continue;
+                        }
+                        truncated = true;
+                        break;
+                    }
+
                     let block = match block {
                         Err(e) => {
                             error!("result row write failed: {:?}", e);
@@ -214,14 +318,19 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                                     &e.display_with_sql(&query_result.sql).to_string().as_bytes(),
                                 )
                                 .await?;
-                            return Ok(());
+                            return Ok(None);
                         }
                         Ok(block) => block,
                     };
 
-                    let num_rows = block.num_rows();
-                    let encoder = FieldEncoderValues::create_for_mysql_handler(format.timezone);
-                    let mut buf = Vec::<u8>::new();
+                    let mut num_rows = block.num_rows();
+                    if let Some(max_rows) = max_rows {
+                        if written_rows + num_rows > max_rows {
+                            num_rows = max_rows - written_rows;
+                            truncated = true;
+                        }
+                    }
+                    written_rows += num_rows;
 
                     let columns = block
                         .convert_to_full()
@@ -232,6 +341,11 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
 
                     for row_index in 0..num_rows {
                         for (_col_index, column) in columns.iter().enumerate() {
+                            // A NULL-only column has neither values nor validity, every row is NULL.
+                            if let ExprColumn::Null { .. } = column {
+                                row_writer.write_col(None::<u8>)?;
+                                continue;
+                            }
                             let value = unsafe { column.index_unchecked(row_index) };
                             match value {
                                 ScalarRef::Null => {
@@ -272,33 +386,84 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
                                             &encoder,
                                             &mut buf,
                                             row_index,
+                                            false,
                                         )?;
                                     }
                                 },
+                                // Top-level strings are written unchanged by the encoder, hand the
+                                // bytes to the row writer directly instead of copying them into `buf`.
+                                ScalarRef::String(v) if !format.quote_strings => {
+                                    row_writer.write_col(v)?;
+                                }
+                                ScalarRef::String(_) | ScalarRef::Variant(_) => write_field(
+                                    &mut row_writer,
+                                    column,
+                                    &encoder,
+                                    &mut buf,
+                                    row_index,
+                                    format.quote_strings,
+                                )?,
                                 ScalarRef::Bitmap(_) => {
                                     let bitmap_result = "<bitmap binary>".as_bytes();
                                     row_writer.write_col(bitmap_result)?;
                                 }
+                                ScalarRef::Map(_) => {
+                                    // Render maps as JSON object text so that clients can parse them.
+                                    // Only top-level maps are covered, maps nested in arrays or tuples
+                                    // keep the `{k:v}` text of the values encoder.
+                                    buf.clear();
+                                    json_encoder.write_field(column, row_index, &mut buf);
+                                    row_writer.write_col(&buf[..])?;
+                                }
+                                // Tuples are rendered as `(1,'a',NULL)`, the elements are written like
+                                // nested values: strings are quoted and NULLs use `null_string`.
+                                ScalarRef::Tuple(_) => write_field(
+                                    &mut row_writer,
+                                    column,
+                                    &encoder,
+                                    &mut buf,
+                                    row_index,
+                                    false,
+                                )?,
                                 _ => write_field(
                                     &mut row_writer,
                                     column,
                                     &encoder,
                                     &mut buf,
                                     row_index,
+                                    false,
                                 )?,
                             }
                         }
                         row_writer.end_row().await?;
                     }
+
+                    if truncated {
+                        break;
+                    }
                 }
 
-                let info = query_result
+                if more_results {
+                    // The progress info only goes into the final packet of the last result set.
+                    return Ok(Some(row_writer.finish_one().await?));
+                }
+
+                let mut info = query_result
                     .extra_info
                     .map(|r| r.progress_info())
                     .unwrap_or_default();
+                if truncated {
+                    if !info.is_empty() {
+                        info.push_str("; ");
+                    }
+                    info.push_str(&format!(
+                        "Warning: result truncated to {} rows",
+                        written_rows
+                    ));
+                }
                 row_writer.finish_with_info(&info).await?;
 
-                Ok(())
+                Ok(None)
             }
         }
     }
@@ -322,3 +487,362 @@ impl<'a, W: AsyncWrite + Send + Unpin> DFQueryResultWriter<'a, W> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use databend_common_arrow::arrow::bitmap::Bitmap;
+    use databend_common_base::base::tokio;
+    use databend_common_base::base::tokio::io::AsyncWrite;
+    use databend_common_base::base::tokio::net::TcpListener;
+    use databend_common_exception::ErrorCode;
+    use databend_common_exception::Result;
+    use databend_common_expression::types::array::ArrayColumn;
+    use databend_common_expression::types::nullable::NullableColumn;
+    use databend_common_expression::types::DataType;
+    use databend_common_expression::types::Int32Type;
+    use databend_common_expression::types::NumberDataType;
+    use databend_common_expression::types::StringType;
+    use databend_common_expression::types::TimestampType;
+    use databend_common_expression::types::UInt64Type;
+    use databend_common_expression::Column;
+    use databend_common_expression::DataBlock;
+    use databend_common_expression::DataField;
+    use databend_common_expression::DataSchemaRef;
+    use databend_common_expression::DataSchemaRefExt;
+    use databend_common_expression::FromData;
+    use databend_common_io::prelude::FormatSettings;
+    use futures_util::StreamExt;
+    use mysql_async::consts::ColumnType as MySqlColumnType;
+    use mysql_async::prelude::Queryable;
+    use opensrv_mysql::AsyncMysqlIntermediary;
+    use opensrv_mysql::AsyncMysqlShim;
+    use opensrv_mysql::ColumnType;
+    use opensrv_mysql::ErrorKind;
+    use opensrv_mysql::OkResponse;
+    use opensrv_mysql::ParamParser;
+    use opensrv_mysql::QueryResultWriter;
+    use opensrv_mysql::StatementMetaWriter;
+
+    use super::ColumnTypeOverride;
+    use super::DFQueryResultWriter;
+    use super::QueryResult;
+    use crate::stream::DataBlockStream;
+
+    fn block_result(
+        schema: DataSchemaRef,
+        block: DataBlock,
+        query: &str,
+        format: Option<FormatSettings>,
+    ) -> Result<(QueryResult, Option<FormatSettings>)> {
+        let blocks = DataBlockStream::create(None, vec![block]).boxed();
+        let result = QueryResult::create(blocks, None, true, schema, query.to_string());
+        Ok((result, format))
+    }
+
+    fn query_result(value: u64) -> Result<(QueryResult, Option<FormatSettings>)> {
+        let schema = DataSchemaRefExt::create(vec![DataField::new(
+            "n",
+            DataType::Number(NumberDataType::UInt64),
+        )]);
+        let block = DataBlock::new_from_columns(vec![UInt64Type::from_data(vec![value])]);
+        block_result(schema, block, &format!("SELECT {value}"), None)
+    }
+
+    fn large_string() -> String {
+        "0123456789".repeat(1024 * 1024)
+    }
+
+    /// Answers `two results` and `error in the middle` with several result sets,
+    /// `nulls` with a top-level and a nested NULL, rendered with `null_string = '\N'`,
+    /// `large string` with a single 10MB string cell, `quoted strings` with a
+    /// string rendered with `quote_strings` enabled, `tuples` with a nullable
+    /// `Tuple(Int32, String)` and a nested tuple, and `timestamp` with the epoch in the
+    /// `Asia/Shanghai` time zone of the query while the default format is UTC. `types`
+    /// answers with a count and a string column, `types as long` does the same with
+    /// `MYSQL_TYPE_LONGLONG` columns sent as `MYSQL_TYPE_LONG`.
+    struct MultiResultShim;
+
+    #[async_trait::async_trait]
+    impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MultiResultShim {
+        type Error = ErrorCode;
+
+        async fn on_prepare<'a>(
+            &'a mut self,
+            _query: &'a str,
+            writer: StatementMetaWriter<'a, W>,
+        ) -> Result<()> {
+            writer
+                .error(ErrorKind::ER_UNKNOWN_ERROR, "unsupported".as_bytes())
+                .await?;
+            Ok(())
+        }
+
+        async fn on_execute<'a>(
+            &'a mut self,
+            _id: u32,
+            _param: ParamParser<'a>,
+            writer: QueryResultWriter<'a, W>,
+        ) -> Result<()> {
+            writer
+                .error(ErrorKind::ER_UNKNOWN_ERROR, "unsupported".as_bytes())
+                .await?;
+            Ok(())
+        }
+
+        async fn on_close<'a>(&'a mut self, _stmt_id: u32)
+        where W: 'async_trait {
+        }
+
+        async fn on_query<'a>(
+            &'a mut self,
+            query: &'a str,
+            writer: QueryResultWriter<'a, W>,
+        ) -> Result<()> {
+            let mut column_type_override: Option<ColumnTypeOverride> = None;
+            let results = match query {
+                "two results" => vec![query_result(1), query_result(2)],
+                "error in the middle" => vec![
+                    query_result(1),
+                    Err(ErrorCode::Internal("second statement failed")),
+                    query_result(3),
+                ],
+                "nulls" => {
+                    let schema = DataSchemaRefExt::create(vec![
+                        DataField::new_nullable("n", DataType::Number(NumberDataType::UInt64)),
+                        DataField::new(
+                            "a",
+                            DataType::Array(Box::new(
+                                DataType::Number(NumberDataType::UInt64).wrap_nullable(),
+                            )),
+                        ),
+                    ]);
+                    let array = Column::Array(Box::new(ArrayColumn {
+                        values: UInt64Type::from_opt_data(vec![Some(1), None]),
+                        offsets: vec![0, 2].into(),
+                    }));
+                    let block = DataBlock::new_from_columns(vec![
+                        UInt64Type::from_opt_data(vec![None]),
+                        array,
+                    ]);
+                    let format = FormatSettings {
+                        null_string: "\\N".to_string(),
+                        ..FormatSettings::default()
+                    };
+                    vec![block_result(schema, block, query, Some(format))]
+                }
+                "quoted strings" => {
+                    let schema = DataSchemaRefExt::create(vec![
+                        DataField::new("s", DataType::String),
+                        DataField::new_nullable("n", DataType::String),
+                        DataField::new("i", DataType::Number(NumberDataType::UInt64)),
+                    ]);
+                    let block = DataBlock::new_from_columns(vec![
+                        StringType::from_data(vec!["it's"]),
+                        StringType::from_opt_data(vec![Some("a")]),
+                        UInt64Type::from_data(vec![1]),
+                    ]);
+                    let format = FormatSettings {
+                        quote_strings: true,
+                        ..FormatSettings::default()
+                    };
+                    vec![block_result(schema, block, query, Some(format))]
+                }
+                "tuples" => {
+                    let int_type = DataType::Number(NumberDataType::Int32);
+                    let tuple_type = DataType::Tuple(vec![int_type.clone(), DataType::String]);
+                    let nested_type = DataType::Tuple(vec![
+                        int_type.clone(),
+                        DataType::Tuple(vec![DataType::String, int_type.wrap_nullable()]),
+                    ]);
+                    let schema = DataSchemaRefExt::create(vec![
+                        DataField::new("t", tuple_type.wrap_nullable()),
+                        DataField::new("n", nested_type),
+                    ]);
+                    let tuple = Column::Nullable(Box::new(NullableColumn {
+                        column: Column::Tuple(vec![
+                            Int32Type::from_data(vec![1, 2]),
+                            StringType::from_data(vec!["a'b", "c"]),
+                        ]),
+                        validity: Bitmap::from([true, false]),
+                    }));
+                    let nested = Column::Tuple(vec![
+                        Int32Type::from_data(vec![3, 4]),
+                        Column::Tuple(vec![
+                            StringType::from_data(vec!["x", "y"]),
+                            Int32Type::from_opt_data(vec![None, Some(5)]),
+                        ]),
+                    ]);
+                    let block = DataBlock::new_from_columns(vec![tuple, nested]);
+                    vec![block_result(schema, block, query, None)]
+                }
+                "timestamp" => {
+                    let schema =
+                        DataSchemaRefExt::create(vec![DataField::new("ts", DataType::Timestamp)]);
+                    let block =
+                        DataBlock::new_from_columns(vec![TimestampType::from_data(vec![0])]);
+                    let format = FormatSettings {
+                        timezone: "Asia/Shanghai".parse().unwrap(),
+                        ..FormatSettings::default()
+                    };
+                    vec![block_result(schema, block, query, Some(format))]
+                }
+                "types" | "types as long" => {
+                    if query == "types as long" {
+                        column_type_override = Some(Arc::new(|_, column_type| match column_type {
+                            ColumnType::MYSQL_TYPE_LONGLONG => ColumnType::MYSQL_TYPE_LONG,
+                            other => other,
+                        }));
+                    }
+                    let schema = DataSchemaRefExt::create(vec![
+                        DataField::new("count()", DataType::Number(NumberDataType::UInt64)),
+                        DataField::new("name", DataType::String),
+                    ]);
+                    let block = DataBlock::new_from_columns(vec![
+                        UInt64Type::from_data(vec![3]),
+                        StringType::from_data(vec!["a"]),
+                    ]);
+                    vec![block_result(schema, block, query, None)]
+                }
+                "large string" => {
+                    let schema =
+                        DataSchemaRefExt::create(vec![DataField::new("s", DataType::String)]);
+                    let block = DataBlock::new_from_columns(vec![StringType::from_data(vec![
+                        large_string(),
+                    ])]);
+                    vec![block_result(schema, block, query, None)]
+                }
+                _ => {
+                    writer.completed(OkResponse::default()).await?;
+                    return Ok(());
+                }
+            };
+            let mut writer = DFQueryResultWriter::create(writer, column_type_override);
+            writer.write_many(results, &FormatSettings::default()).await
+        }
+    }
+
+    /// Start a MySQL server answering with [`MultiResultShim`] and connect to it.
+    async fn connect() -> mysql_async::Conn {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let (r, w) = stream.into_split();
+                tokio::spawn(AsyncMysqlIntermediary::run_on(MultiResultShim, r, w));
+            }
+        });
+
+        let opts = mysql_async::OptsBuilder::default()
+            .ip_or_hostname("127.0.0.1")
+            .user(Some("root".to_string()))
+            .tcp_port(port);
+        mysql_async::Conn::new(opts).await.unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_write_many() {
+        let mut conn = connect().await;
+        let mut result = conn.query_iter("two results").await.unwrap();
+        let first: Vec<u64> = result.collect().await.unwrap();
+        let second: Vec<u64> = result.collect().await.unwrap();
+        assert_eq!(first, vec![1]);
+        assert_eq!(second, vec![2]);
+        assert!(result.is_empty());
+        drop(result);
+
+        // The error is sent in place of the second result set and the third is never written.
+        let mut conn = connect().await;
+        let mut result = conn.query_iter("error in the middle").await.unwrap();
+        let first: Vec<u64> = result.collect().await.unwrap();
+        assert_eq!(first, vec![1]);
+        let err = result.collect::<u64>().await.unwrap_err();
+        assert!(err.to_string().contains("second statement failed"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_null_string() {
+        let mut conn = connect().await;
+
+        // The top-level NULL stays a protocol NULL, the nested one uses `null_string`.
+        let rows: Vec<(Option<u64>, Option<String>)> = conn.query("nulls").await.unwrap();
+        assert_eq!(rows, vec![(None, Some("[1,\\N]".to_string()))]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quote_strings() {
+        let mut conn = connect().await;
+
+        // Strings are quoted with embedded quotes doubled, numbers are left alone.
+        let rows: Vec<(String, String, u64)> = conn.query("quoted strings").await.unwrap();
+        assert_eq!(rows, vec![("'it''s'".to_string(), "'a'".to_string(), 1)]);
+
+        // By default strings are sent as is.
+        let rows: Vec<String> = conn.query("large string").await.unwrap();
+        assert!(!rows[0].starts_with('\''));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_tuples() {
+        let mut conn = connect().await;
+
+        // A NULL tuple is a protocol NULL, NULL elements are rendered as `NULL`.
+        let rows: Vec<(Option<String>, String)> = conn.query("tuples").await.unwrap();
+        assert_eq!(rows, vec![
+            (Some("(1,'a''b')".to_string()), "(3,('x',NULL))".to_string()),
+            (None, "(4,('y',5))".to_string()),
+        ]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_query_timezone() {
+        let mut conn = connect().await;
+
+        // The time zone of the query overrides the UTC default of the writer.
+        let rows: Vec<String> = conn.query("timestamp").await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].starts_with("1970-01-01 08:00:00"), "{}", rows[0]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_large_string() {
+        let mut conn = connect().await;
+
+        // The cell is written straight from the column buffer and must arrive intact.
+        let rows: Vec<String> = conn.query("large string").await.unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 10 * 1024 * 1024);
+        assert!(rows[0] == large_string());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_column_type_override() {
+        let mut conn = connect().await;
+
+        let mut result = conn.query_iter("types").await.unwrap();
+        let columns = result.columns().unwrap();
+        assert_eq!(
+            columns[0].column_type(),
+            MySqlColumnType::MYSQL_TYPE_LONGLONG
+        );
+        assert_eq!(
+            columns[1].column_type(),
+            MySqlColumnType::MYSQL_TYPE_VARCHAR
+        );
+        let rows: Vec<(u64, String)> = result.collect().await.unwrap();
+        assert_eq!(rows, vec![(3, "a".to_string())]);
+        drop(result);
+
+        // Only the count column is remapped, the value is still read as a number.
+        let mut result = conn.query_iter("types as long").await.unwrap();
+        let columns = result.columns().unwrap();
+        assert_eq!(columns[0].column_type(), MySqlColumnType::MYSQL_TYPE_LONG);
+        assert_eq!(
+            columns[1].column_type(),
+            MySqlColumnType::MYSQL_TYPE_VARCHAR
+        );
+        let rows: Vec<(u64, String)> = result.collect().await.unwrap();
+        assert_eq!(rows, vec![(3, "a".to_string())]);
+    }
+}
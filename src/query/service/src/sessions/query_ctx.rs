@@ -56,6 +56,8 @@ use databend_common_expression::date_helper::TzFactory;
 use databend_common_expression::DataBlock;
 use databend_common_expression::Expr;
 use databend_common_expression::FunctionContext;
+use databend_common_io::constants::NULL_BYTES_UPPER;
+use databend_common_io::prelude::FloatFormat;
 use databend_common_io::prelude::FormatSettings;
 use databend_common_meta_app::principal::FileFormatParams;
 use databend_common_meta_app::principal::OnErrorMode;
@@ -569,7 +571,20 @@ impl TableContext for QueryContext {
         let timezone = tz.parse::<Tz>().map_err(|_| {
             ErrorCode::InvalidTimezone("Timezone has been checked and should be valid")
         })?;
-        let format = FormatSettings { timezone };
+        let float_format = self
+            .query_settings
+            .get_float_format()?
+            .parse::<FloatFormat>()?;
+        let null_string = match self.query_settings.get_null_string()? {
+            null_string if null_string.is_empty() => NULL_BYTES_UPPER.to_string(),
+            null_string => null_string,
+        };
+        let format = FormatSettings {
+            timezone,
+            null_string,
+            float_format,
+            quote_strings: self.query_settings.get_quote_strings()?,
+        };
         Ok(format)
     }
 
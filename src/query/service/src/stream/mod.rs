@@ -17,8 +17,10 @@ mod table_read_block_stream;
 
 mod datablock_stream;
 mod progress_stream;
+mod rechunk_stream;
 
 pub use datablock_stream::DataBlockStream;
 pub use processor_executor_stream::PullingExecutorStream;
 pub use progress_stream::ProgressStream;
+pub use rechunk_stream::RechunkStream;
 pub use table_read_block_stream::ReadDataBlockStream;
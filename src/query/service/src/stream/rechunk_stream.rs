@@ -0,0 +1,101 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::task::Context;
+use std::task::Poll;
+
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::DataBlock;
+use databend_common_expression::SendableDataBlockStream;
+use futures::Stream;
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Re-splits the blocks of `input` so that every output block has exactly
+    /// `target_rows` rows, except the last one which holds the remainder.
+    pub struct RechunkStream {
+        #[pin]
+        input: SendableDataBlockStream,
+        target_rows: usize,
+        buffer: Vec<DataBlock>,
+        buffered_rows: usize,
+        finished: bool,
+    }
+}
+
+impl RechunkStream {
+    pub fn try_create(input: SendableDataBlockStream, target_rows: usize) -> Result<Self> {
+        if target_rows == 0 {
+            return Err(ErrorCode::BadArguments(
+                "target rows of RechunkStream must be greater than 0",
+            ));
+        }
+        Ok(Self {
+            input,
+            target_rows,
+            buffer: vec![],
+            buffered_rows: 0,
+            finished: false,
+        })
+    }
+}
+
+impl Stream for RechunkStream {
+    type Item = Result<DataBlock>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        ctx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            if *this.buffered_rows >= *this.target_rows {
+                let block = match DataBlock::concat(this.buffer) {
+                    Ok(block) => block,
+                    Err(e) => return Poll::Ready(Some(Err(e))),
+                };
+                this.buffer.clear();
+                *this.buffered_rows -= *this.target_rows;
+                if *this.buffered_rows > 0 {
+                    this.buffer
+                        .push(block.slice(*this.target_rows..block.num_rows()));
+                }
+                return Poll::Ready(Some(Ok(block.slice(0..*this.target_rows))));
+            }
+
+            if *this.finished {
+                if this.buffer.is_empty() {
+                    return Poll::Ready(None);
+                }
+                let blocks = std::mem::take(this.buffer);
+                *this.buffered_rows = 0;
+                return Poll::Ready(Some(DataBlock::concat(&blocks)));
+            }
+
+            match this.input.as_mut().poll_next(ctx) {
+                Poll::Ready(Some(Ok(block))) => {
+                    if !block.is_empty() {
+                        *this.buffered_rows += block.num_rows();
+                        this.buffer.push(block);
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => return Poll::Ready(Some(Err(e))),
+                Poll::Ready(None) => *this.finished = true,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
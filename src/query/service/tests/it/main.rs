@@ -37,5 +37,6 @@ mod sessions;
 mod spillers;
 mod sql;
 mod storages;
+mod stream;
 mod table_functions;
 mod tests;
@@ -0,0 +1,471 @@
+// Copyright 2023 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io::Write;
+use std::sync::Arc;
+
+use databend_common_base::base::tokio;
+use databend_common_base::base::tokio::sync::mpsc::channel;
+use databend_common_base::base::Progress;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::Result;
+use databend_common_expression::types::BooleanType;
+use databend_common_expression::types::Int32Type;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::types::StringType;
+use databend_common_expression::BlockThresholds;
+use databend_common_expression::DataBlock;
+use databend_common_expression::FromData;
+use databend_common_expression::TableDataType;
+use databend_common_expression::TableField;
+use databend_common_expression::TableSchemaRefExt;
+use databend_common_formats::FileFormatOptionsExt;
+use databend_common_meta_app::principal::CsvFileFormatParams;
+use databend_common_meta_app::principal::FileFormatParams;
+use databend_common_meta_app::principal::NdJsonFileFormatParams;
+use databend_common_meta_app::principal::NullAs;
+use databend_common_meta_app::principal::OnErrorMode;
+use databend_common_pipeline_sources::input_formats::read_csv_blocks;
+use databend_common_pipeline_sources::input_formats::read_csv_blocks_from_bytes;
+use databend_common_pipeline_sources::input_formats::read_fixed_width_blocks;
+use databend_common_pipeline_sources::input_formats::read_ndjson_blocks;
+use databend_common_pipeline_sources::input_formats::read_parquet_blocks;
+use databend_common_pipeline_sources::input_formats::AligningStateTextBased;
+use databend_common_pipeline_sources::input_formats::BlockBuilderTrait;
+use databend_common_pipeline_sources::input_formats::InputContext;
+use databend_common_pipeline_sources::input_formats::InputFormatCSV;
+use databend_common_pipeline_sources::input_formats::InputFormatTextBase;
+use databend_common_pipeline_sources::input_formats::SplitInfo;
+use databend_common_settings::Settings;
+use databend_query::test_kits::TestFixture;
+use futures::TryStreamExt;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_csv_blocks() -> Result<()> {
+    let mut file = tempfile::Builder::new().suffix(".csv").tempfile()?;
+    file.write_all(b"id,name\n1,a\n2,\\N\n3,\"c,d\"\n4,e\n5,f")?;
+
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String.wrap_nullable()),
+    ]);
+    let params = CsvFileFormatParams {
+        headers: 1,
+        ..Default::default()
+    };
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    let path = file.path().to_str().unwrap();
+    let (stream, rejected_rows) = read_csv_blocks(
+        path,
+        schema.clone(),
+        &params,
+        &options,
+        2,
+        OnErrorMode::default(),
+    )?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    assert!(rejected_rows.lock().is_empty());
+
+    assert_eq!(
+        blocks.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data(vec![1, 2, 3, 4, 5])
+    );
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_opt_data(vec![Some("a"), None, Some("c,d"), Some("e"), Some("f")])
+    );
+
+    // A row with a missing field aborts the stream.
+    let mut file = tempfile::Builder::new().suffix(".csv").tempfile()?;
+    file.write_all(b"id,name\n1,a\n2\n")?;
+    let path = file.path().to_str().unwrap();
+    let (stream, _) = read_csv_blocks(path, schema, &params, &options, 2, OnErrorMode::default())?;
+    let result: Result<Vec<_>> = stream.try_collect().await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_csv_blocks_on_error() -> Result<()> {
+    let mut file = tempfile::Builder::new().suffix(".csv").tempfile()?;
+    file.write_all(
+        b"1,a
+x,b
+3,c
+4
+5,e
+",
+    )?;
+    let path = file.path().to_str().unwrap();
+
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String),
+    ]);
+    let params = CsvFileFormatParams::default();
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    // Skip all the bad rows and keep them aside.
+    let (stream, rejected_rows) = read_csv_blocks(
+        path,
+        schema.clone(),
+        &params,
+        &options,
+        2,
+        OnErrorMode::Continue,
+    )?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data(vec![1, 3, 5])
+    );
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_data(vec!["a", "c", "e"])
+    );
+    let rejected_rows = rejected_rows.lock();
+    assert_eq!(
+        rejected_rows
+            .iter()
+            .map(|row| (row.line, row.data.clone()))
+            .collect::<Vec<_>>(),
+        vec![(2, b"x,b".to_vec()), (4, b"4".to_vec())]
+    );
+
+    // Abort on the second bad row.
+    let (stream, rejected_rows) =
+        read_csv_blocks(path, schema, &params, &options, 2, OnErrorMode::AbortNum(2))?;
+    let result: Result<Vec<_>> = stream.try_collect().await;
+    assert!(
+        result
+            .unwrap_err()
+            .message()
+            .contains("abort after 2 errors")
+    );
+    assert_eq!(rejected_rows.lock().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_csv_blocks_multiline() -> Result<()> {
+    let mut file = tempfile::Builder::new().suffix(".csv").tempfile()?;
+    file.write_all(b"1,\"line1\nline2\"\n2,\"a\r\nb\nc\"\nx,d\n3,e\n")?;
+    let path = file.path().to_str().unwrap();
+
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String),
+    ]);
+    let params = CsvFileFormatParams::default();
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    let (stream, rejected_rows) =
+        read_csv_blocks(path, schema, &params, &options, 2, OnErrorMode::Continue)?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(block.num_rows(), 3);
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_data(vec!["line1\nline2", "a\r\nb\nc", "e"])
+    );
+
+    // Quoted line breaks are counted when reporting where a bad row starts.
+    let rejected_rows = rejected_rows.lock();
+    assert_eq!(
+        rejected_rows
+            .iter()
+            .map(|row| (row.line, row.data.clone()))
+            .collect::<Vec<_>>(),
+        vec![(6, b"x,d".to_vec())]
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_csv_blocks_from_bytes() -> Result<()> {
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String),
+    ]);
+    let params = CsvFileFormatParams::default();
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    let (stream, rejected_rows) = read_csv_blocks_from_bytes(
+        b"1,a\n2,\"b,c\"\nx,d\n3,e".to_vec(),
+        schema,
+        &params,
+        &options,
+        10,
+        OnErrorMode::Continue,
+    )?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data(vec![1, 2, 3])
+    );
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_data(vec!["a", "b,c", "e"])
+    );
+    assert_eq!(rejected_rows.lock().len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_input_block_rows() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+    let settings = ctx.get_settings();
+    settings
+        .set_setting("input_block_rows".to_string(), "4".to_string())
+        .await?;
+
+    let schema = TableSchemaRefExt::create(vec![TableField::new(
+        "id",
+        TableDataType::Number(NumberDataType::Int32),
+    )]);
+    let (_tx, rx) = channel(1);
+    let input_ctx = Arc::new(
+        InputContext::try_create_from_insert_file_format(
+            ctx.clone(),
+            rx,
+            settings,
+            FileFormatParams::Csv(CsvFileFormatParams::default()),
+            schema,
+            Arc::new(Progress::create()),
+            false,
+            BlockThresholds::default(),
+            OnErrorMode::AbortNum(1),
+        )
+        .await?,
+    );
+
+    let split_info = Arc::new(SplitInfo::from_stream_split("test.csv".to_string(), None));
+    let mut aligner = InputFormatCSV::try_create_align_state(&input_ctx, &split_info)?;
+    let mut builder = InputFormatCSV::try_create_block_builder(&input_ctx)?;
+
+    // 10 rows are read in one batch, the last block holds the 2 remaining rows.
+    let data = (0..10).map(|i| format!("{i}\n")).collect::<String>();
+    let mut row_batches = aligner.align(data.as_bytes())?;
+    row_batches.extend(aligner.align_flush()?);
+
+    let mut blocks = vec![];
+    for batch in row_batches {
+        blocks.extend(builder.deserialize(Some(batch))?);
+    }
+    blocks.extend(builder.deserialize(None)?);
+
+    assert_eq!(
+        blocks.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![4, 4, 2]
+    );
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data((0..10).collect::<Vec<_>>())
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_fixed_width_blocks() -> Result<()> {
+    let mut file = tempfile::Builder::new().suffix(".txt").tempfile()?;
+    file.write_all(
+        b"ID  NAME  FLAG \n   1alice true \n  22  \\N  false\r\n   xbob   true \n\n  33carol",
+    )?;
+    let path = file.path().to_str().unwrap();
+
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String.wrap_nullable()),
+        TableField::new("flag", TableDataType::Boolean),
+    ]);
+    let params = CsvFileFormatParams {
+        headers: 1,
+        ..Default::default()
+    };
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    let (stream, rejected_rows) = read_fixed_width_blocks(
+        path,
+        schema.clone(),
+        &[4, 6, 5],
+        &params,
+        &options,
+        2,
+        OnErrorMode::Continue,
+    )?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data(vec![1, 22, 33])
+    );
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_opt_data(vec![Some("alice"), None, Some("carol")])
+    );
+    // The flag of the last, short line is empty.
+    assert_eq!(
+        block.get_by_offset(2).value.as_column().unwrap(),
+        &BooleanType::from_data(vec![true, false, false])
+    );
+    let rejected_rows = rejected_rows.lock();
+    assert_eq!(
+        rejected_rows
+            .iter()
+            .map(|row| (row.line, row.data.clone()))
+            .collect::<Vec<_>>(),
+        vec![(4, b"   xbob   true ".to_vec())]
+    );
+
+    // Every column needs a width.
+    let result = read_fixed_width_blocks(
+        path,
+        schema,
+        &[4, 6],
+        &params,
+        &options,
+        2,
+        OnErrorMode::Continue,
+    );
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_ndjson_blocks() -> Result<()> {
+    let mut file = tempfile::Builder::new().suffix(".ndjson").tempfile()?;
+    file.write_all(
+        br#"{"id": 1, "name": "a"}
+{"id": 2}
+
+{"id": "x", "name": "c"}
+{"name": "d", "id": 4}
+"#,
+    )?;
+    let path = file.path().to_str().unwrap();
+
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+        TableField::new("name", TableDataType::String.wrap_nullable()),
+    ]);
+    let params = NdJsonFileFormatParams {
+        missing_field_as: NullAs::Null,
+        ..Default::default()
+    };
+    let settings = Settings::create("default".to_string());
+    let options = FileFormatOptionsExt::create_from_settings(&settings, false)?;
+
+    let (stream, rejected_rows) = read_ndjson_blocks(
+        path,
+        schema.clone(),
+        &params,
+        &options,
+        2,
+        OnErrorMode::Continue,
+    )?;
+    let blocks: Vec<_> = stream.try_collect().await?;
+    assert_eq!(
+        blocks.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(
+        block.get_by_offset(0).value.as_column().unwrap(),
+        &Int32Type::from_data(vec![1, 2, 4])
+    );
+    assert_eq!(
+        block.get_by_offset(1).value.as_column().unwrap(),
+        &StringType::from_opt_data(vec![Some("a"), None, Some("d")])
+    );
+    let rejected_rows = rejected_rows.lock();
+    assert_eq!(
+        rejected_rows
+            .iter()
+            .map(|row| (row.line, row.data.clone()))
+            .collect::<Vec<_>>(),
+        vec![(4, br#"{"id": "x", "name": "c"}"#.to_vec())]
+    );
+
+    // A missing key is an error by default.
+    let params = NdJsonFileFormatParams::default();
+    let (stream, _) =
+        read_ndjson_blocks(path, schema, &params, &options, 2, OnErrorMode::default())?;
+    let result: Result<Vec<_>> = stream.try_collect().await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_read_parquet_blocks() -> Result<()> {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../tests/data/parquet/alltypes_plain.parquet"
+    );
+
+    // Only `bool_col` and `id` are read, in the order of the schema.
+    let schema = TableSchemaRefExt::create(vec![
+        TableField::new("bool_col", TableDataType::Boolean),
+        TableField::new("id", TableDataType::Number(NumberDataType::Int32)),
+    ]);
+    let blocks: Vec<_> = read_parquet_blocks(path, schema)?.try_collect().await?;
+    let block = DataBlock::concat(&blocks)?;
+    assert_eq!(block.num_rows(), 8);
+    assert_eq!(block.num_columns(), 2);
+    assert_eq!(
+        block
+            .get_by_offset(0)
+            .value
+            .as_column()
+            .unwrap()
+            .remove_nullable(),
+        BooleanType::from_data(vec![true, false, true, false, true, false, true, false])
+    );
+    assert_eq!(
+        block
+            .get_by_offset(1)
+            .value
+            .as_column()
+            .unwrap()
+            .remove_nullable(),
+        Int32Type::from_data(vec![4, 5, 6, 7, 2, 3, 0, 1])
+    );
+
+    let schema =
+        TableSchemaRefExt::create(vec![TableField::new("no_such_col", TableDataType::Boolean)]);
+    assert!(read_parquet_blocks(path, schema).is_err());
+
+    Ok(())
+}
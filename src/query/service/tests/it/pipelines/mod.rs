@@ -14,3 +14,4 @@
 
 mod executor;
 mod filter;
+mod input_formats;
@@ -109,3 +109,26 @@ fn test_empty_block() -> Result<()> {
     assert!(json_block.is_empty());
     Ok(())
 }
+
+#[test]
+fn test_null_string() -> Result<()> {
+    let schema = DataSchemaRefExt::create(vec![DataField::new_nullable(
+        "c1",
+        DataType::Number(NumberDataType::Int32),
+    )]);
+    let block = DataBlock::new_from_columns(vec![Int32Type::from_opt_data(vec![Some(1), None])]);
+
+    let format = FormatSettings::default();
+    let json_block = JsonBlock::new(schema.clone(), &block, &format)?;
+    let expect = vec![vec![val("1")], vec![val("NULL")]];
+    assert_eq!(json_block.data().clone(), expect);
+
+    let format = FormatSettings {
+        null_string: "\\N".to_string(),
+        ..FormatSettings::default()
+    };
+    let json_block = JsonBlock::new(schema, &block, &format)?;
+    let expect = vec![vec![val("1")], vec![val("\\N")]];
+    assert_eq!(json_block.data().clone(), expect);
+    Ok(())
+}
@@ -192,6 +192,131 @@ async fn test_rejected_session_with_parallel() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+async fn test_max_result_rows_with_warning() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs, MySQLTlsConfig::default())?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port(), false).await?;
+
+    connection
+        .query_drop("SET mysql_max_result_rows = 100")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Set setting failed")?;
+    let rows: Vec<u64> = connection
+        .query("SELECT number FROM numbers(1000)")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+
+    assert_eq!(rows.len(), 100);
+    assert!(
+        connection
+            .info()
+            .contains("Warning: result truncated to 100 rows")
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_max_result_rows_without_warning() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs, MySQLTlsConfig::default())?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port(), false).await?;
+
+    connection
+        .query_drop("SET max_block_size = 3")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Set setting failed")?;
+    connection
+        .query_drop("SET mysql_max_result_rows = 6")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Set setting failed")?;
+    // The 6 rows are split across blocks, and the filter may leave the last block empty.
+    let mut rows: Vec<u64> = connection
+        .query("SELECT number FROM numbers(9) WHERE number < 6")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+
+    rows.sort();
+    assert_eq!(rows, vec![0, 1, 2, 3, 4, 5]);
+    assert!(!connection.info().contains("Warning: result truncated"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_text_fields_across_blocks() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs, MySQLTlsConfig::default())?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port(), false).await?;
+
+    connection
+        .query_drop("SET max_block_size = 3")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Set setting failed")?;
+    let rows: Vec<(Option<String>, Option<String>)> = connection
+        .query(
+            "SELECT if(number % 2 = 0, NULL, repeat('数据', number)), \
+             if(number % 3 = 0, 'ü', NULL) FROM numbers(10) ORDER BY number",
+        )
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+
+    assert_eq!(rows.len(), 10);
+    for (number, (lhs, rhs)) in rows.into_iter().enumerate() {
+        let expected = (number % 2 != 0).then(|| "数据".repeat(number));
+        assert_eq!(lhs, expected);
+        assert_eq!(rhs, (number % 3 == 0).then(|| "ü".to_string()));
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_null_only_columns() -> Result<()> {
+    let _fixture = TestFixture::setup().await?;
+
+    let tcp_keepalive_timeout_secs = 120;
+    let mut handler = MySQLHandler::create(tcp_keepalive_timeout_secs, MySQLTlsConfig::default())?;
+
+    let listening = "127.0.0.1:0".parse::<SocketAddr>()?;
+    let runnable_server = handler.start(listening).await?;
+    let mut connection = create_connection(runnable_server.port(), false).await?;
+
+    let rows: Vec<Option<String>> = connection
+        .query("SELECT NULL")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+    assert_eq!(rows, vec![None]);
+
+    let rows: Vec<(Option<u64>, Option<String>)> = connection
+        .query("SELECT number, NULL FROM numbers(3) ORDER BY number")
+        .await
+        .map_err_to_code(ErrorCode::UnknownException, || "Query failed")?;
+    assert_eq!(rows, vec![
+        (Some(0), None),
+        (Some(1), None),
+        (Some(2), None)
+    ]);
+
+    Ok(())
+}
+
 async fn create_connection(port: u16, with_tls: bool) -> Result<mysql_async::Conn> {
     let ssl_opts = if with_tls {
         Some(SslOpts::default().with_root_cert_path(Some(Path::new(TEST_CA_CERT))))
@@ -65,3 +65,27 @@ async fn test_get_storage_accessor_fs() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn test_get_format_settings() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    let format = ctx.get_format_settings()?;
+    assert_eq!(format.null_string, "NULL");
+
+    ctx.get_settings()
+        .set_setting("null_string".to_string(), "\\N".to_string())
+        .await?;
+    let format = ctx.get_format_settings()?;
+    assert_eq!(format.null_string, "\\N");
+
+    assert!(!format.quote_strings);
+    ctx.get_settings()
+        .set_setting("quote_strings".to_string(), "1".to_string())
+        .await?;
+    let format = ctx.get_format_settings()?;
+    assert!(format.quote_strings);
+
+    Ok(())
+}
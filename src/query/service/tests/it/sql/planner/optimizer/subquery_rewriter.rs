@@ -0,0 +1,426 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_ast::parser::parse_sql;
+use databend_common_ast::parser::tokenize_sql;
+use databend_common_ast::Dialect;
+use databend_common_base::base::tokio;
+use databend_common_catalog::catalog::CatalogManager;
+use databend_common_catalog::table_context::TableContext;
+use databend_common_exception::ErrorCode;
+use databend_common_exception::Result;
+use databend_common_expression::Scalar;
+use databend_common_sql::optimizer::OptimizerContext;
+use databend_common_sql::optimizer::RecursiveOptimizer;
+use databend_common_sql::optimizer::RuleID;
+use databend_common_sql::optimizer::SExpr;
+use databend_common_sql::optimizer::SubqueryRewriter;
+use databend_common_sql::optimizer::DEFAULT_REWRITE_RULES;
+use databend_common_sql::plans::ConstantExpr;
+use databend_common_sql::plans::Filter;
+use databend_common_sql::plans::Join;
+use databend_common_sql::plans::JoinType;
+use databend_common_sql::plans::Plan;
+use databend_common_sql::plans::RelOperator;
+use databend_common_sql::plans::ScalarExpr;
+use databend_common_sql::Binder;
+use databend_common_sql::Metadata;
+use databend_common_sql::MetadataRef;
+use databend_common_sql::NameResolutionContext;
+use databend_query::test_kits::TestFixture;
+use parking_lot::RwLock;
+
+async fn rewrite_sql(ctx: Arc<dyn TableContext>, sql: &str) -> Result<SExpr> {
+    let (s_expr, metadata) = bind_sql(ctx, sql).await?;
+    SubqueryRewriter::new(metadata).rewrite(&s_expr)
+}
+
+async fn optimize_sql(ctx: Arc<dyn TableContext>, sql: &str, rules: &[RuleID]) -> Result<SExpr> {
+    let (s_expr, metadata) = bind_sql(ctx.clone(), sql).await?;
+    let s_expr = SubqueryRewriter::new(metadata.clone()).rewrite(&s_expr)?;
+    RecursiveOptimizer::new(rules, &OptimizerContext::new(ctx, metadata)).run(&s_expr)
+}
+
+async fn bind_sql(ctx: Arc<dyn TableContext>, sql: &str) -> Result<(SExpr, MetadataRef)> {
+    let settings = ctx.get_settings();
+    let metadata = Arc::new(RwLock::new(Metadata::default()));
+    let name_resolution_ctx = NameResolutionContext::try_from(settings.as_ref())?;
+    let binder = Binder::new(
+        ctx.clone(),
+        CatalogManager::instance(),
+        name_resolution_ctx,
+        metadata,
+    );
+    let tokens = tokenize_sql(sql)?;
+    let (stmt, _) = parse_sql(&tokens, Dialect::PostgreSQL)?;
+    let Plan::Query {
+        s_expr, metadata, ..
+    } = binder.bind(&stmt).await?
+    else {
+        unreachable!()
+    };
+    Ok((*s_expr, metadata))
+}
+
+fn find_join(s_expr: &SExpr) -> Option<Join> {
+    if let RelOperator::Join(join) = s_expr.plan() {
+        return Some(join.clone());
+    }
+    s_expr.children().iter().find_map(|child| find_join(child))
+}
+
+fn find_join_expr(s_expr: &SExpr) -> Option<SExpr> {
+    if let RelOperator::Join(_) = s_expr.plan() {
+        return Some(s_expr.clone());
+    }
+    s_expr
+        .children()
+        .iter()
+        .find_map(|child| find_join_expr(child))
+}
+
+fn contains_aggregate(s_expr: &SExpr) -> bool {
+    matches!(s_expr.plan(), RelOperator::Aggregate(_))
+        || s_expr
+            .children()
+            .iter()
+            .any(|child| contains_aggregate(child))
+}
+
+fn find_filter(s_expr: &SExpr) -> Option<Filter> {
+    if let RelOperator::Filter(filter) = s_expr.plan() {
+        return Some(filter.clone());
+    }
+    s_expr
+        .children()
+        .iter()
+        .find_map(|child| find_filter(child))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_exists_subquery_drops_filter() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The EXISTS predicate is unnested into a cross join and replaced with `TRUE`,
+    // so the outer filter is removed. The only filter left is the `count(*) = 1`
+    // of the rewritten subquery.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT * FROM numbers(3) WHERE EXISTS (SELECT 1)",
+    )
+    .await?;
+    let join_expr = find_join_expr(&s_expr).unwrap();
+    assert!(find_filter(join_expr.child(0)?).is_none());
+    let filter = find_filter(&s_expr).unwrap();
+    assert!(matches!(filter.predicates.as_slice(), [
+        ScalarExpr::FunctionCall(func)
+    ] if func.func_name == "eq"));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_constant_in_subquery() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // A constant compared with `=` becomes an equi condition of the mark join.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE 5 IN (SELECT number FROM numbers(10))",
+    )
+    .await?;
+    let join = find_join(&s_expr).unwrap();
+    assert_eq!(join.join_type, JoinType::RightMark);
+    assert_eq!(join.left_conditions.len(), 1);
+    assert_eq!(join.right_conditions.len(), 1);
+    assert!(join.non_equi_conditions.is_empty());
+
+    // Other comparisons still need a non-equi condition.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE 5 > ANY(SELECT number FROM numbers(10))",
+    )
+    .await?;
+    let join = find_join(&s_expr).unwrap();
+    assert!(join.left_conditions.is_empty());
+    assert_eq!(join.non_equi_conditions.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_constant_scalar_subquery() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // A constant-only scalar subquery is folded, no join is built for it.
+    let s_expr = rewrite_sql(ctx.clone(), "SELECT (SELECT 2)").await?;
+    assert!(find_join(&s_expr).is_none());
+
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE number = (SELECT 1 + 1)",
+    )
+    .await?;
+    assert!(find_join(&s_expr).is_none());
+
+    // A subquery reading a table still needs the join.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE number = (SELECT max(number) FROM numbers(2))",
+    )
+    .await?;
+    assert!(find_join(&s_expr).is_some());
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_correlated_scalar_subquery_single_join() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The single join is what makes the executor reject a correlated scalar
+    // subquery that returns more than one row for an outer row.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT (SELECT t.number FROM numbers(3) AS t WHERE t.number >= n.number) \
+         FROM numbers(2) AS n",
+    )
+    .await?;
+    let join = find_join(&s_expr).unwrap();
+    assert_eq!(join.join_type, JoinType::LeftSingle);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_eliminate_self_comparison() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // `number = number` is always true, the filter is dropped.
+    let s_expr = optimize_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE number = number",
+        &[RuleID::EliminateFilter],
+    )
+    .await?;
+    assert!(find_filter(&s_expr).is_none());
+
+    // `number <> number` is always false.
+    let s_expr = optimize_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(3) WHERE number <> number",
+        &[RuleID::EliminateFilter],
+    )
+    .await?;
+    let filter = find_filter(&s_expr).unwrap();
+    assert!(matches!(filter.predicates.as_slice(), [
+        ScalarExpr::ConstantExpr(ConstantExpr {
+            value: Scalar::Boolean(false),
+            ..
+        })
+    ]));
+
+    // A nullable column compared with itself may be NULL, it is kept as is.
+    let s_expr = optimize_sql(
+        ctx.clone(),
+        "SELECT n FROM (SELECT number::Nullable(UInt64) AS n FROM numbers(3)) AS t WHERE n = n",
+        &[RuleID::EliminateFilter],
+    )
+    .await?;
+    let filter = find_filter(&s_expr).unwrap();
+    assert!(matches!(filter.predicates.as_slice(), [
+        ScalarExpr::FunctionCall(_)
+    ]));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_correlated_subquery_in_having() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The subquery is correlated with a group item, so it has to be joined with the
+    // aggregated rows rather than with the rows of `t1` before aggregation.
+    let s_expr = rewrite_sql(
+        ctx.clone(),
+        "SELECT t1.number % 3 AS k, sum(t1.number) AS x FROM numbers(10) AS t1 GROUP BY k \
+         HAVING x > (SELECT avg(t2.number) FROM numbers(10) AS t2 WHERE t2.number % 3 = k)",
+    )
+    .await?;
+    let join = find_join_expr(&s_expr).unwrap();
+    assert!(contains_aggregate(join.child(0)?));
+    assert!(!matches!(s_expr.plan(), RelOperator::Aggregate(_)));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_push_down_filter_below_mark_join() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The marker is only used in a disjunction, so the mark join is not turned into
+    // a semi join. `t.number > 1` does not depend on the marker and is pushed below it.
+    let (s_expr, metadata) = bind_sql(
+        ctx.clone(),
+        "SELECT t.number FROM numbers(10) AS t WHERE t.number > 1 \
+         AND (t.number IN (SELECT number FROM numbers(5)) OR t.number = 8)",
+    )
+    .await?;
+    let s_expr = SubqueryRewriter::new(metadata.clone()).rewrite(&s_expr)?;
+    let s_expr = RecursiveOptimizer::new(
+        &DEFAULT_REWRITE_RULES,
+        &OptimizerContext::new(ctx.clone(), metadata),
+    )
+    .run(&s_expr)?;
+
+    let join_expr = find_join_expr(&s_expr).unwrap();
+    let RelOperator::Join(join) = join_expr.plan() else {
+        unreachable!()
+    };
+    assert_eq!(join.join_type, JoinType::RightMark);
+    assert!(find_filter(join_expr.child(0)?).is_some());
+    let filter = find_filter(&s_expr).unwrap();
+    assert_eq!(filter.predicates.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_push_down_distinct_mark_join() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    let optimize = |s_expr: SExpr, metadata: MetadataRef| -> Result<SExpr> {
+        let opt_ctx = OptimizerContext::new(ctx.clone(), metadata.clone());
+        let s_expr = SubqueryRewriter::new(metadata).rewrite(&s_expr)?;
+        let s_expr = RecursiveOptimizer::new(&DEFAULT_REWRITE_RULES, &opt_ctx).run(&s_expr)?;
+        RecursiveOptimizer::new(&[RuleID::PushDownDistinctMarkJoin], &opt_ctx).run(&s_expr)
+    };
+
+    // The mark join is kept because of the disjunction, its build side is deduplicated
+    // on the subquery output.
+    let (s_expr, metadata) = bind_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(10) WHERE number IN (SELECT number % 3 FROM numbers(100)) \
+         OR number = 8",
+    )
+    .await?;
+    let s_expr = optimize(s_expr, metadata)?;
+    let join_expr = find_join_expr(&s_expr).unwrap();
+    let RelOperator::Join(join) = join_expr.plan() else {
+        unreachable!()
+    };
+    assert_eq!(join.join_type, JoinType::RightMark);
+    let RelOperator::Aggregate(distinct) = join_expr.child(1)?.plan() else {
+        panic!("expect a distinct, got {:?}", join_expr.child(1)?.plan());
+    };
+    assert!(distinct.from_distinct);
+    assert!(distinct.aggregate_functions.is_empty());
+    assert_eq!(distinct.group_items.len(), 1);
+    assert_eq!(
+        join.right_conditions[0].used_columns(),
+        distinct.group_items[0].scalar.used_columns()
+    );
+
+    // An aggregated subquery is already distinct.
+    let (s_expr, metadata) = bind_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(10) WHERE number IN \
+         (SELECT number % 3 FROM numbers(100) GROUP BY number % 3) OR number = 8",
+    )
+    .await?;
+    let s_expr = optimize(s_expr, metadata)?;
+    let join_expr = find_join_expr(&s_expr).unwrap();
+    let build = join_expr.child(1)?;
+    assert!(!matches!(build.plan(), RelOperator::Aggregate(agg) if agg.from_distinct));
+
+    // A semi join does not need a distinct build side.
+    let (s_expr, metadata) = bind_sql(
+        ctx.clone(),
+        "SELECT number FROM numbers(10) WHERE number IN (SELECT number FROM numbers(100))",
+    )
+    .await?;
+    let s_expr = optimize(s_expr, metadata)?;
+    let join_expr = find_join_expr(&s_expr).unwrap();
+    let RelOperator::Join(join) = join_expr.plan() else {
+        unreachable!()
+    };
+    assert_eq!(join.join_type, JoinType::LeftSemi);
+    assert!(!contains_aggregate(&join_expr));
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_empty_uncorrelated_scalar_subquery() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The subquery is aggregated without GROUP BY, so it yields exactly one row even
+    // when it is empty, and the cross join keeps every outer row with a NULL value.
+    for sql in [
+        "SELECT number, (SELECT number FROM numbers(3) WHERE number > 10) FROM numbers(2)",
+        "SELECT (SELECT 1 WHERE false)",
+    ] {
+        let s_expr = rewrite_sql(ctx.clone(), sql).await?;
+        let join_expr = find_join_expr(&s_expr).unwrap();
+        let RelOperator::Join(join) = join_expr.plan() else {
+            unreachable!()
+        };
+        assert_eq!(join.join_type, JoinType::Cross);
+
+        let mut subquery = join_expr.child(1)?;
+        if let RelOperator::Limit(_) = subquery.plan() {
+            subquery = subquery.child(0)?;
+        }
+        let RelOperator::Aggregate(agg) = subquery.plan() else {
+            panic!("expect an aggregate, got {:?}", subquery.plan());
+        };
+        assert!(agg.group_items.is_empty());
+    }
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_unsupported_correlated_subquery() -> Result<()> {
+    let fixture = TestFixture::setup().await?;
+    let ctx = fixture.new_query_ctx().await?;
+
+    // The outer column is referenced below a window, which cannot be flattened.
+    let sql = "SELECT number FROM numbers(3) AS t WHERE EXISTS \
+               (SELECT row_number() OVER (ORDER BY t2.number) FROM numbers(3) AS t2 \
+               WHERE t2.number = t.number)";
+    let Err(err) = rewrite_sql(ctx.clone(), sql).await else {
+        panic!("expect the correlated subquery to be rejected");
+    };
+    assert_eq!(err.code(), ErrorCode::UNSUPPORTED_CORRELATED_SUBQUERY);
+    assert!(
+        err.message()
+            .contains("outer columns referenced under a Window operator are not supported"),
+        "{}",
+        err.message()
+    );
+
+    Ok(())
+}
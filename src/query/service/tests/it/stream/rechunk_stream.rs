@@ -0,0 +1,59 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_base::base::tokio;
+use databend_common_exception::Result;
+use databend_common_expression::types::Int32Type;
+use databend_common_expression::DataBlock;
+use databend_common_expression::FromData;
+use databend_query::stream::DataBlockStream;
+use databend_query::stream::RechunkStream;
+use futures::TryStreamExt;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rechunk_stream() -> Result<()> {
+    let mut next = 0;
+    let blocks = [3, 0, 1, 7, 2, 4]
+        .into_iter()
+        .map(|rows| {
+            let values = (next..next + rows).collect::<Vec<i32>>();
+            next += rows;
+            DataBlock::new_from_columns(vec![Int32Type::from_data(values)])
+        })
+        .collect::<Vec<_>>();
+
+    let input = Box::pin(DataBlockStream::create(None, blocks));
+    let output = RechunkStream::try_create(input, 4)?
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    // 17 rows in total: four full blocks and a final one with the remainder.
+    let rows = output.iter().map(|b| b.num_rows()).collect::<Vec<_>>();
+    assert_eq!(rows, vec![4, 4, 4, 4, 1]);
+
+    // Rows keep their order across the input boundaries.
+    let merged = DataBlock::concat(&output)?;
+    let expected =
+        DataBlock::new_from_columns(vec![Int32Type::from_data((0..17).collect::<Vec<i32>>())]);
+    assert_eq!(merged.columns(), expected.columns());
+
+    let input = Box::pin(DataBlockStream::create(None, vec![]));
+    let output = RechunkStream::try_create(input, 4)?
+        .try_collect::<Vec<_>>()
+        .await?;
+    assert!(output.is_empty());
+
+    assert!(RechunkStream::try_create(Box::pin(DataBlockStream::create(None, vec![])), 0).is_err());
+    Ok(())
+}
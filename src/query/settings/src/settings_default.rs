@@ -205,12 +205,36 @@ impl DefaultSettings {
                     mode: SettingMode::Both,
                     range: None,
                 }),
+                ("input_block_rows", DefaultSettingValue {
+                    value: UserSettingValue::UInt64(0),
+                    desc: "Sets the number of rows in each block produced when reading text files such as CSV, the last block may be smaller. Setting it to 0 means the block size is decided by the block thresholds.",
+                    mode: SettingMode::Both,
+                    range: None,
+                }),
                 ("timezone", DefaultSettingValue {
                     value: UserSettingValue::String("UTC".to_owned()),
                     desc: "Sets the timezone.",
                     mode: SettingMode::Both,
                     range: None,
                 }),
+                ("float_format", DefaultSettingValue {
+                    value: UserSettingValue::String("shortest".to_owned()),
+                    desc: "Sets how floats are rendered in query results: 'shortest', 'fixed(n)' or 'scientific(n)'.",
+                    mode: SettingMode::Both,
+                    range: None,
+                }),
+                ("null_string", DefaultSettingValue {
+                    value: UserSettingValue::String("".to_owned()),
+                    desc: "Sets the text NULL values are rendered as in query results and in CSV and TSV output. An empty value keeps the default of each format: NULL for query results and \\N for CSV and TSV.",
+                    mode: SettingMode::Both,
+                    range: None,
+                }),
+                ("quote_strings", DefaultSettingValue {
+                    value: UserSettingValue::UInt64(0),
+                    desc: "Enables quoting and escaping string and variant values in query results sent over the MySQL text protocol.",
+                    mode: SettingMode::Both,
+                    range: Some(SettingRange::Numeric(0..=1)),
+                }),
                 ("group_by_two_level_threshold", DefaultSettingValue {
                     value: UserSettingValue::UInt64(20000),
                     desc: "Sets the number of keys in a GROUP BY operation that will trigger a two-level aggregation.",
@@ -290,6 +314,12 @@ impl DefaultSettings {
                     mode: SettingMode::Both,
                     range: None,
                 }),
+                ("mysql_max_result_rows", DefaultSettingValue {
+                    value: UserSettingValue::UInt64(0),
+                    desc: "Sets the maximum number of rows the MySQL handler streams back to the client, the rest are dropped with a warning. Setting it to 0 means no limit.",
+                    mode: SettingMode::Both,
+                    range: None,
+                }),
                 ("prefer_broadcast_join", DefaultSettingValue {
                     value: UserSettingValue::UInt64(1),
                     desc: "Enables broadcast join.",
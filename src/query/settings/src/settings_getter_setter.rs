@@ -232,6 +232,10 @@ impl Settings {
         self.try_get_u64("input_read_buffer_size")
     }
 
+    pub fn get_input_block_rows(&self) -> Result<u64> {
+        self.try_get_u64("input_block_rows")
+    }
+
     pub fn get_enable_bushy_join(&self) -> Result<u64> {
         self.try_get_u64("enable_bushy_join")
     }
@@ -240,6 +244,18 @@ impl Settings {
         self.try_get_string("timezone")
     }
 
+    pub fn get_float_format(&self) -> Result<String> {
+        self.try_get_string("float_format")
+    }
+
+    pub fn get_null_string(&self) -> Result<String> {
+        self.try_get_string("null_string")
+    }
+
+    pub fn get_quote_strings(&self) -> Result<bool> {
+        Ok(self.try_get_u64("quote_strings")? != 0)
+    }
+
     // Get group by two level threshold
     pub fn get_group_by_two_level_threshold(&self) -> Result<u64> {
         self.try_get_u64("group_by_two_level_threshold")
@@ -261,6 +277,10 @@ impl Settings {
         self.try_get_u64("max_result_rows")
     }
 
+    pub fn get_mysql_max_result_rows(&self) -> Result<u64> {
+        self.try_get_u64("mysql_max_result_rows")
+    }
+
     pub fn get_enable_dphyp(&self) -> Result<bool> {
         Ok(self.try_get_u64("enable_dphyp")? != 0)
     }
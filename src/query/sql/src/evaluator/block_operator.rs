@@ -83,7 +83,7 @@ impl BlockOperator {
             BlockOperator::Project { projection } => {
                 let mut result = DataBlock::new(vec![], input.num_rows());
                 for index in projection {
-                    result.add_column(input.get_by_offset(*index).clone());
+                    result.add_column(input.try_get_by_offset(*index)?.clone());
                 }
                 Ok(result)
             }
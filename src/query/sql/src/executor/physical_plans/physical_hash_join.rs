@@ -16,7 +16,6 @@ use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 use databend_common_expression::type_check::check_cast;
 use databend_common_expression::type_check::common_super_type;
-use databend_common_expression::types::DataType;
 use databend_common_expression::ConstantFolder;
 use databend_common_expression::DataField;
 use databend_common_expression::DataSchemaRef;
@@ -345,70 +344,36 @@ impl PhysicalPlanBuilder {
         merged_fields.extend(tail_fields);
         let merged_schema = DataSchemaRefExt::create(merged_fields);
 
-        let merged_fields = match join.join_type {
-            JoinType::Cross
-            | JoinType::Inner
-            | JoinType::Left
-            | JoinType::LeftSingle
-            | JoinType::Right
-            | JoinType::RightSingle
-            | JoinType::Full => {
-                probe_fields.extend(build_fields);
-                probe_fields
-            }
-            JoinType::LeftSemi | JoinType::LeftAnti | JoinType::RightSemi | JoinType::RightAnti => {
-                let (result_fields, dropped_fields) = if join.join_type == JoinType::LeftSemi
-                    || join.join_type == JoinType::LeftAnti
-                {
-                    (probe_fields, build_fields)
+        if matches!(
+            join.join_type,
+            JoinType::LeftSemi | JoinType::LeftAnti | JoinType::RightSemi | JoinType::RightAnti
+        ) {
+            let (result_fields, dropped_fields) =
+                if join.join_type == JoinType::LeftSemi || join.join_type == JoinType::LeftAnti {
+                    (&probe_fields, &build_fields)
                 } else {
-                    (build_fields, probe_fields)
+                    (&build_fields, &probe_fields)
                 };
-                for field in dropped_fields.iter() {
-                    if result_fields.iter().all(|x| x.name() != field.name()) &&
-                        let Ok(index) = field.name().parse::<usize>() &&
-                        column_projections.contains(&index)
-                    {
-                        let metadata = self.metadata.read();
-                        let unexpected_column = metadata.column(index);
-                        let unexpected_column_info = if let Some(table_index) = unexpected_column.table_index() {
-                            format!("{:?}.{:?}", metadata.table(table_index).name(), unexpected_column.name())
-                        } else {
-                            unexpected_column.name().to_string()
-                        };
-                        return Err(ErrorCode::SemanticError(format!(
-                            "cannot access the {} in ANTI or SEMI join",
-                            unexpected_column_info
-                        )));
-                    }
+            for field in dropped_fields.iter() {
+                if result_fields.iter().all(|x| x.name() != field.name()) &&
+                    let Ok(index) = field.name().parse::<usize>() &&
+                    column_projections.contains(&index)
+                {
+                    let metadata = self.metadata.read();
+                    let unexpected_column = metadata.column(index);
+                    let unexpected_column_info = if let Some(table_index) = unexpected_column.table_index() {
+                        format!("{:?}.{:?}", metadata.table(table_index).name(), unexpected_column.name())
+                    } else {
+                        unexpected_column.name().to_string()
+                    };
+                    return Err(ErrorCode::SemanticError(format!(
+                        "cannot access the {} in ANTI or SEMI join",
+                        unexpected_column_info
+                    )));
                 }
-                result_fields
-            }
-            JoinType::LeftMark => {
-                let name = if let Some(idx) = join.marker_index {
-                    idx.to_string()
-                } else {
-                    "marker".to_string()
-                };
-                build_fields.push(DataField::new(
-                    name.as_str(),
-                    DataType::Nullable(Box::new(DataType::Boolean)),
-                ));
-                build_fields
-            }
-            JoinType::RightMark => {
-                let name = if let Some(idx) = join.marker_index {
-                    idx.to_string()
-                } else {
-                    "marker".to_string()
-                };
-                probe_fields.push(DataField::new(
-                    name.as_str(),
-                    DataType::Nullable(Box::new(DataType::Boolean)),
-                ));
-                probe_fields
             }
-        };
+        }
+        let merged_fields = join.output_fields(&probe_fields, &build_fields);
         let mut projections = ColumnSet::new();
         let projected_schema = DataSchemaRefExt::create(merged_fields.clone());
         for column in column_projections.iter() {
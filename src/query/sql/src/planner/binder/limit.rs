@@ -16,6 +16,8 @@ use std::sync::Arc;
 
 use databend_common_ast::ast::Expr;
 use databend_common_ast::ast::Literal;
+use databend_common_ast::ast::SelectTarget;
+use databend_common_ast::ast::SetExpr;
 use databend_common_exception::ErrorCode;
 use databend_common_exception::Result;
 
@@ -29,18 +31,12 @@ impl Binder {
         offset: &Option<Expr>,
     ) -> Result<(Option<usize>, usize)> {
         let limit_cnt = match limit {
-            Some(limit) => Some(
-                Self::bind_limit_argument(limit)
-                    .ok_or_else(|| ErrorCode::SemanticError("Invalid LIMIT expression"))?
-                    as usize,
-            ),
+            Some(limit) => Some(Self::bind_limit_argument(limit, "LIMIT")? as usize),
             None => None,
         };
 
         let offset_cnt = if let Some(offset) = offset {
-            Self::bind_limit_argument(offset)
-                .ok_or_else(|| ErrorCode::SemanticError("Invalid OFFSET expression"))?
-                as usize
+            Self::bind_limit_argument(offset, "OFFSET")? as usize
         } else {
             0
         };
@@ -57,15 +53,61 @@ impl Binder {
         SExpr::create_unary(Arc::new(limit_plan.into()), Arc::new(child))
     }
 
-    /// So far, we only support integer literal as limit argument.
+    /// So far, we only support integer literal, or a subquery selecting one, as limit argument.
     /// So we will try to extract the integer value from the AST directly.
-    /// In the future it's possible to treat the argument as an expression.
-    fn bind_limit_argument(expr: &Expr) -> Option<u64> {
+    /// Subqueries that read a table, e.g. `LIMIT (SELECT n FROM cfg)`, are not supported
+    /// since the binder can't run a query. In the future it's possible to treat the argument
+    /// as an expression.
+    fn bind_limit_argument(expr: &Expr, clause: &str) -> Result<u64> {
+        if let Some(value) = Self::constant_limit_argument(expr) {
+            return Ok(value);
+        }
+        match expr {
+            Expr::Subquery { .. } => Err(ErrorCode::SemanticError(format!(
+                "{clause} only supports a subquery that selects an integer literal, \
+                 such as `{clause} (SELECT 10)`, subqueries reading tables are not supported"
+            ))),
+            _ => Err(ErrorCode::SemanticError(format!(
+                "Invalid {clause} expression"
+            ))),
+        }
+    }
+
+    /// A subquery that only selects an integer literal, e.g. `LIMIT (SELECT 10)`,
+    /// is folded into that literal, the same way as a constant scalar subquery.
+    fn constant_limit_argument(expr: &Expr) -> Option<u64> {
         match expr {
             Expr::Literal {
                 lit: Literal::UInt64(value),
                 ..
             } => Some(*value),
+            Expr::Subquery {
+                modifier: None,
+                subquery,
+                ..
+            } if subquery.with.is_none()
+                && subquery.order_by.is_empty()
+                && subquery.limit.is_empty()
+                && subquery.offset.is_none() =>
+            {
+                let SetExpr::Select(select) = &subquery.body else {
+                    return None;
+                };
+                if select.distinct
+                    || !select.from.is_empty()
+                    || select.selection.is_some()
+                    || select.group_by.is_some()
+                    || select.having.is_some()
+                    || select.window_list.is_some()
+                    || select.qualify.is_some()
+                {
+                    return None;
+                }
+                match select.select_list.as_slice() {
+                    [SelectTarget::AliasedExpr { expr, .. }] => Self::constant_limit_argument(expr),
+                    _ => None,
+                }
+            }
             _ => None,
         }
     }
@@ -51,6 +51,7 @@ use crate::MetadataRef;
 /// Correlated scalar subquery -> Single join
 /// Any subquery -> Marker join
 /// Correlated exists subquery -> Marker join
+/// Correlated not exists subquery in a conjunctive predicate -> Anti join
 ///
 /// More information can be found in the paper: Unnesting Arbitrary Queries
 pub fn decorrelate_subquery(metadata: MetadataRef, s_expr: SExpr) -> Result<SExpr> {
@@ -318,6 +319,35 @@ impl SubqueryRewriter {
                     flatten_info,
                     false,
                 )?;
+                if is_conjunctive_predicate && subquery.typ == SubqueryType::NotExists {
+                    // A top-level `NOT EXISTS` conjunct can be rewritten into an anti join
+                    // directly, instead of a mark join followed by `not(marker)`.
+                    // Outer rows without any match in the subquery are kept.
+                    let mut left_conditions = Vec::with_capacity(correlated_columns.len());
+                    let mut right_conditions = Vec::with_capacity(correlated_columns.len());
+                    self.add_equi_conditions(
+                        subquery.span,
+                        &correlated_columns,
+                        &mut right_conditions,
+                        &mut left_conditions,
+                    )?;
+                    let join_plan = Join {
+                        left_conditions,
+                        right_conditions,
+                        non_equi_conditions: vec![],
+                        join_type: JoinType::LeftAnti,
+                        marker_index: None,
+                        from_correlated_subquery: true,
+                        need_hold_hash_table: false,
+                        broadcast: false,
+                    };
+                    let s_expr = SExpr::create_binary(
+                        Arc::new(join_plan.into()),
+                        Arc::new(left.clone()),
+                        Arc::new(flatten_plan),
+                    );
+                    return Ok((s_expr, UnnestResult::SimpleJoin));
+                }
                 // Construct mark join
                 let mut left_conditions = Vec::with_capacity(correlated_columns.len());
                 let mut right_conditions = Vec::with_capacity(correlated_columns.len());
@@ -35,6 +35,7 @@ use crate::plans::EvalScalar;
 use crate::plans::Filter;
 use crate::plans::Join;
 use crate::plans::JoinType;
+use crate::plans::Operator;
 use crate::plans::ProjectSet;
 use crate::plans::RelOperator;
 use crate::plans::ScalarExpr;
@@ -191,9 +192,10 @@ impl SubqueryRewriter {
                 self.flatten_union_all(op, plan, correlated_columns, flatten_info, need_cross_join)
             }
 
-            _ => Err(ErrorCode::Internal(
-                "Invalid plan type for flattening subquery",
-            )),
+            _ => Err(ErrorCode::UnsupportedCorrelatedSubquery(format!(
+                "correlated subquery cannot be decorrelated: outer columns referenced under a {:?} operator are not supported",
+                plan.plan().rel_op()
+            ))),
         }
     }
 
@@ -105,9 +105,10 @@ impl SubqueryRewriter {
                     arguments,
                 }))
             }
-            _ => Err(ErrorCode::Internal(
-                "Invalid scalar for flattening subquery",
-            )),
+            _ => Err(ErrorCode::UnsupportedCorrelatedSubquery(
+                "correlated subquery cannot be decorrelated: outer columns referenced in window functions, lambda functions or nested subqueries are not supported",
+            )
+            .set_span(scalar.span())),
         }
     }
 }
@@ -28,6 +28,7 @@ use crate::binder::wrap_cast;
 use crate::binder::ColumnBindingBuilder;
 use crate::binder::Visibility;
 use crate::optimizer::RelExpr;
+use crate::optimizer::RelationalProperty;
 use crate::optimizer::SExpr;
 use crate::plans::Aggregate;
 use crate::plans::AggregateFunction;
@@ -69,6 +70,16 @@ pub struct FlattenInfo {
 pub struct SubqueryRewriter {
     pub(crate) metadata: MetadataRef,
     pub(crate) derived_columns: HashMap<IndexType, IndexType>,
+    /// Relational properties of the subqueries seen so far, keyed by the
+    /// rewritten subquery, so that a subquery bound more than once (e.g.
+    /// through an alias referenced in several clauses) is only derived once.
+    ///
+    /// `SExpr` already caches `rel_prop` per node, so this map only helps
+    /// for equal copies of a subquery built separately by the binder. Each
+    /// lookup hashes and compares the whole subquery tree.
+    subquery_props: HashMap<SExpr, Arc<RelationalProperty>>,
+    /// Number of subqueries whose relational property was not in `subquery_props`.
+    subquery_prop_misses: usize,
 }
 
 impl SubqueryRewriter {
@@ -76,9 +87,21 @@ impl SubqueryRewriter {
         Self {
             metadata,
             derived_columns: Default::default(),
+            subquery_props: Default::default(),
+            subquery_prop_misses: 0,
         }
     }
 
+    fn derive_subquery_prop(&mut self, subquery: &SExpr) -> Result<Arc<RelationalProperty>> {
+        if let Some(prop) = self.subquery_props.get(subquery) {
+            return Ok(prop.clone());
+        }
+        self.subquery_prop_misses += 1;
+        let prop = RelExpr::with_s_expr(subquery).derive_relational_prop()?;
+        self.subquery_props.insert(subquery.clone(), prop.clone());
+        Ok(prop)
+    }
+
     pub fn rewrite(&mut self, s_expr: &SExpr) -> Result<SExpr> {
         match s_expr.plan().clone() {
             RelOperator::EvalScalar(mut plan) => {
@@ -93,6 +116,8 @@ impl SubqueryRewriter {
                 Ok(SExpr::create_unary(Arc::new(plan.into()), Arc::new(input)))
             }
             RelOperator::Filter(mut plan) => {
+                // For `HAVING` the input is the aggregate, so a correlated subquery is joined
+                // above it and its outer columns refer to the group items or aggregates.
                 let mut input = self.rewrite(s_expr.child(0)?)?;
                 for pred in plan.predicates.iter_mut() {
                     let res = self.try_rewrite_subquery(pred, &input, true)?;
@@ -100,6 +125,21 @@ impl SubqueryRewriter {
                     *pred = res.0;
                 }
 
+                // Subqueries unnested into simple joins are replaced with `TRUE`,
+                // drop them and the whole `Filter` if nothing else is left.
+                plan.predicates.retain(|pred| {
+                    !matches!(
+                        pred,
+                        ScalarExpr::ConstantExpr(ConstantExpr {
+                            value: Scalar::Boolean(true),
+                            ..
+                        })
+                    )
+                });
+                if plan.predicates.is_empty() {
+                    return Ok(input);
+                }
+
                 Ok(SExpr::create_unary(Arc::new(plan.into()), Arc::new(input)))
             }
             RelOperator::ProjectSet(mut plan) => {
@@ -214,12 +254,21 @@ impl SubqueryRewriter {
             }
             ScalarExpr::CastExpr(cast) => {
                 let (scalar, s_expr) = self.try_rewrite_subquery(&cast.argument, s_expr, false)?;
+                // `FunctionCall` derives its return type from the arguments, but the cast keeps
+                // the target type chosen by the binder. If the argument became nullable after
+                // replacing a subquery with a join output column, the target type has to follow,
+                // just like `TypeChecker` does for a nullable source.
+                let target_type = if scalar.data_type()?.is_nullable_or_null() {
+                    Box::new(cast.target_type.wrap_nullable())
+                } else {
+                    cast.target_type.clone()
+                };
                 Ok((
                     CastExpr {
                         span: cast.span,
                         is_try: cast.is_try,
                         argument: Box::new(scalar),
-                        target_type: cast.target_type.clone(),
+                        target_type,
                     }
                     .into(),
                     s_expr,
@@ -230,11 +279,50 @@ impl SubqueryRewriter {
                 let mut subquery = subquery.clone();
                 subquery.subquery = Box::new(self.rewrite(&subquery.subquery)?);
 
+                // An aggregate without GROUP BY always produces exactly one row, so
+                // `EXISTS` over it is always true and `NOT EXISTS` always false.
+                if matches!(subquery.typ, SubqueryType::Exists | SubqueryType::NotExists)
+                    && is_non_grouped_aggregate(&subquery.subquery)
+                {
+                    return Ok((
+                        ScalarExpr::ConstantExpr(ConstantExpr {
+                            span: subquery.span,
+                            value: Scalar::Boolean(subquery.typ == SubqueryType::Exists),
+                        }),
+                        s_expr.clone(),
+                    ));
+                }
+
+                // A scalar subquery that only projects a constant, e.g. `(SELECT 2)`,
+                // is folded into that constant instead of being joined. The cast keeps
+                // the nullable type the binder gave the subquery.
+                if subquery.typ == SubqueryType::Scalar {
+                    if let Some(value) = constant_scalar_subquery(&subquery) {
+                        return Ok((
+                            CastExpr {
+                                span: subquery.span,
+                                is_try: false,
+                                argument: Box::new(
+                                    ConstantExpr {
+                                        span: subquery.span,
+                                        value,
+                                    }
+                                    .into(),
+                                ),
+                                target_type: subquery.data_type.clone(),
+                            }
+                            .into(),
+                            s_expr.clone(),
+                        ));
+                    }
+                }
+
                 // Check if the subquery is a correlated subquery.
                 // If it is, we'll try to flatten it and rewrite to join.
-                // If it is not, we'll just rewrite it to join
-                let rel_expr = RelExpr::with_s_expr(&subquery.subquery);
-                let prop = rel_expr.derive_relational_prop()?;
+                // If it is not, we'll just rewrite it to join.
+                // The cache is keyed by the rewritten subquery, so a stale entry
+                // for its pre-rewrite form can never be picked up here.
+                let prop = self.derive_subquery_prop(&subquery.subquery)?;
                 let mut flatten_info = FlattenInfo {
                     from_count_func: false,
                 };
@@ -252,6 +340,7 @@ impl SubqueryRewriter {
                 // If we unnest the subquery into a simple join, then we can replace the
                 // original predicate with a `TRUE` literal to eliminate the conjunction.
                 if matches!(result, UnnestResult::SimpleJoin) {
+                    self.derived_columns.clear();
                     return Ok((
                         ScalarExpr::ConstantExpr(ConstantExpr {
                             span: subquery.span,
@@ -392,18 +481,33 @@ impl SubqueryRewriter {
             SubqueryType::Scalar => self.rewrite_uncorrelated_scalar_subquery(left, subquery),
             SubqueryType::Exists | SubqueryType::NotExists => {
                 let mut subquery_expr = *subquery.subquery.clone();
-                // Wrap Limit to current subquery
-                let limit = Limit {
-                    limit: Some(1),
-                    offset: 0,
-                    before_exchange: false,
-                };
-                subquery_expr =
-                    SExpr::create_unary(Arc::new(limit.into()), Arc::new(subquery_expr.clone()));
+                // Limit the subquery to one row. If it already ends with a `Limit`, compose
+                // with it instead of wrapping: the original offset still has to be skipped,
+                // so a row exists only if there are at least `offset + 1` rows.
+                if let RelOperator::Limit(limit) = subquery_expr.plan() {
+                    let limit = Limit {
+                        limit: Some(limit.limit.map_or(1, |limit| limit.min(1))),
+                        offset: limit.offset,
+                        before_exchange: false,
+                    };
+                    subquery_expr = subquery_expr.replace_plan(Arc::new(limit.into()));
+                } else {
+                    let limit = Limit {
+                        limit: Some(1),
+                        offset: 0,
+                        before_exchange: false,
+                    };
+                    subquery_expr = SExpr::create_unary(
+                        Arc::new(limit.into()),
+                        Arc::new(subquery_expr.clone()),
+                    );
+                }
 
                 // We will rewrite EXISTS subquery into the form `COUNT(*) = 1`.
                 // For example, `EXISTS(SELECT a FROM t WHERE a > 1)` will be rewritten into
                 // `(SELECT COUNT(*) = 1 FROM t WHERE a > 1 LIMIT 1)`.
+                // The LIMIT is applied before counting, so a subquery that ends with an
+                // aggregate (e.g. `GROUP BY k`) still yields a count of either 0 or 1 groups.
                 let agg_func = AggregateCountFunction::try_create("", vec![], vec![])?;
                 let agg_func_index = self
                     .metadata
@@ -515,6 +619,8 @@ impl SubqueryRewriter {
                     if !is_non_equi_condition {
                         (vec![left_condition], vec![right_condition], vec![])
                     } else {
+                        // `right_condition` is the child expr, keep it as the left operand so
+                        // that asymmetric operators are not reversed: `a < ANY(..)` => `a < subquery`.
                         let other_condition = ScalarExpr::FunctionCall(FunctionCall {
                             span: subquery.span,
                             func_name: op.to_func_name().to_string(),
@@ -528,6 +634,11 @@ impl SubqueryRewriter {
                 // If subquery contains NULL, the comparison result is TRUE or NULL.
                 // Such as t1.a => {1, 3, 4}, select t1.a in (1, 2, NULL) from t1; The sql will return {true, null, null}.
                 // If subquery doesn't contain NULL, the comparison result is FALSE, TRUE, or NULL.
+                // `a <> ANY(..)` is TRUE as soon as one row differs from `a`, which is exactly what the
+                // non-equi mark condition computes. Unlike `NOT IN` (`<> ALL`) it must not become an anti join.
+                // The equi conditions stay plain equalities, the hash join keeps track of NULLs instead:
+                // a NULL probe key starts with a NULL marker, and once the build side has seen a NULL
+                // key every unmatched marker becomes NULL rather than FALSE.
                 let marker_index = if let Some(idx) = subquery.projection_index {
                     idx
                 } else {
@@ -726,6 +837,40 @@ impl SubqueryRewriter {
     }
 }
 
+/// Returns the projected constant of a subquery shaped like `SELECT <constant>`,
+/// i.e. an `EvalScalar` directly over a `DummyTableScan`.
+fn constant_scalar_subquery(subquery: &SubqueryExpr) -> Option<Scalar> {
+    let RelOperator::EvalScalar(eval_scalar) = subquery.subquery.plan() else {
+        return None;
+    };
+    let child = subquery.subquery.child(0).ok()?;
+    if !matches!(child.plan(), RelOperator::DummyTableScan(_)) {
+        return None;
+    }
+    eval_scalar
+        .items
+        .iter()
+        .find(|item| item.index == subquery.output_column.index)
+        .and_then(|item| match &item.scalar {
+            ScalarExpr::ConstantExpr(constant) => Some(constant.value.clone()),
+            _ => None,
+        })
+}
+
+/// Check if the plan is an aggregate without GROUP BY, optionally topped by
+/// operators that never change its single output row (projections and sorts).
+fn is_non_grouped_aggregate(s_expr: &SExpr) -> bool {
+    match s_expr.plan() {
+        RelOperator::Sort(sort) if sort.limit == Some(0) => false,
+        RelOperator::EvalScalar(_) | RelOperator::Sort(_) => match s_expr.child(0) {
+            Ok(child) => is_non_grouped_aggregate(child),
+            Err(_) => false,
+        },
+        RelOperator::Aggregate(agg) => agg.group_items.is_empty() && agg.grouping_sets.is_none(),
+        _ => false,
+    }
+}
+
 pub fn check_child_expr_in_subquery(
     child_expr: &ScalarExpr,
     op: &ComparisonOp,
@@ -736,12 +881,13 @@ pub fn check_child_expr_in_subquery(
             if func.func_name.eq("tuple") {
                 return Ok((child_expr.clone(), op != &ComparisonOp::Equal));
             }
-            Err(ErrorCode::Internal(format!(
-                "Invalid child expr in subquery: {:?}",
-                child_expr
-            )))
+            // Other scalar functions such as `abs(a)` are compared as a whole
+            // in the non-equi conditions of the mark join.
+            Ok((child_expr.clone(), true))
         }
-        ScalarExpr::ConstantExpr(_) => Ok((child_expr.clone(), true)),
+        // A constant can be an equi key as well, so that `5 IN (SELECT a FROM t)`
+        // probes the hash table instead of comparing every row.
+        ScalarExpr::ConstantExpr(_) => Ok((child_expr.clone(), op != &ComparisonOp::Equal)),
         ScalarExpr::CastExpr(cast) => {
             let arg = &cast.argument;
             let (_, is_non_equi_condition) = check_child_expr_in_subquery(arg, op)?;
@@ -753,3 +899,58 @@ pub fn check_child_expr_in_subquery(
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use databend_common_exception::Result;
+    use databend_common_expression::types::NumberScalar;
+    use databend_common_expression::Scalar;
+    use parking_lot::RwLock;
+
+    use super::SubqueryRewriter;
+    use crate::optimizer::SExpr;
+    use crate::plans::ConstantExpr;
+    use crate::plans::DummyTableScan;
+    use crate::plans::EvalScalar;
+    use crate::plans::RelOperator;
+    use crate::plans::ScalarItem;
+    use crate::Metadata;
+
+    /// `SELECT <value>`, built anew on each call like a subquery bound twice.
+    fn select_constant(value: u64) -> SExpr {
+        let eval_scalar = EvalScalar {
+            items: vec![ScalarItem {
+                scalar: ConstantExpr {
+                    span: None,
+                    value: Scalar::Number(NumberScalar::UInt64(value)),
+                }
+                .into(),
+                index: 0,
+            }],
+        };
+        SExpr::create_unary(
+            Arc::new(RelOperator::EvalScalar(eval_scalar)),
+            Arc::new(SExpr::create_leaf(Arc::new(RelOperator::DummyTableScan(
+                DummyTableScan,
+            )))),
+        )
+    }
+
+    #[test]
+    fn test_subquery_prop_cache() -> Result<()> {
+        let mut rewriter = SubqueryRewriter::new(Arc::new(RwLock::new(Metadata::default())));
+
+        // Equal copies of a subquery are only derived once.
+        rewriter.derive_subquery_prop(&select_constant(1))?;
+        rewriter.derive_subquery_prop(&select_constant(1))?;
+        assert_eq!(rewriter.subquery_prop_misses, 1);
+
+        // Distinct subqueries are derived separately.
+        rewriter.derive_subquery_prop(&select_constant(2))?;
+        assert_eq!(rewriter.subquery_prop_misses, 2);
+
+        Ok(())
+    }
+}
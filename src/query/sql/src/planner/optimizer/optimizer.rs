@@ -225,6 +225,14 @@ pub fn optimize_query(opt_ctx: OptimizerContext, mut s_expr: SExpr) -> Result<SE
     // Run default rewrite rules
     s_expr = RecursiveOptimizer::new(&DEFAULT_REWRITE_RULES, &opt_ctx).run(&s_expr)?;
 
+    // Deduplicate the build side of the remaining mark joins, after mark joins under
+    // a filter have been converted into semi joins by the default rewrite rules.
+    s_expr = RecursiveOptimizer::new(
+        &[RuleID::PushDownDistinctMarkJoin, RuleID::SplitAggregate],
+        &opt_ctx,
+    )
+    .run(&s_expr)?;
+
     // Cost based optimization
     let mut dphyp_optimized = false;
     if opt_ctx.enable_dphyp && opt_ctx.enable_join_reorder {
@@ -291,6 +299,14 @@ fn get_optimized_memo(opt_ctx: OptimizerContext, mut s_expr: SExpr) -> Result<Me
     // Run default rewrite rules
     s_expr = RecursiveOptimizer::new(&DEFAULT_REWRITE_RULES, &opt_ctx).run(&s_expr)?;
 
+    // Deduplicate the build side of the remaining mark joins, after mark joins under
+    // a filter have been converted into semi joins by the default rewrite rules.
+    s_expr = RecursiveOptimizer::new(
+        &[RuleID::PushDownDistinctMarkJoin, RuleID::SplitAggregate],
+        &opt_ctx,
+    )
+    .run(&s_expr)?;
+
     // Cost based optimization
     let mut dphyp_optimized = false;
     if opt_ctx.enable_dphyp && opt_ctx.enable_join_reorder {
@@ -25,6 +25,7 @@ mod rule_merge_filter;
 mod rule_normalize_aggregate;
 mod rule_normalize_disjunctive_filter;
 mod rule_normalize_scalar;
+mod rule_push_down_distinct_mark_join;
 mod rule_push_down_filter_aggregate;
 mod rule_push_down_filter_eval_scalar;
 mod rule_push_down_filter_join;
@@ -56,6 +57,7 @@ pub use rule_merge_filter::RuleMergeFilter;
 pub use rule_normalize_aggregate::RuleNormalizeAggregate;
 pub use rule_normalize_disjunctive_filter::RuleNormalizeDisjunctiveFilter;
 pub use rule_normalize_scalar::RuleNormalizeScalarFilter;
+pub use rule_push_down_distinct_mark_join::RulePushDownDistinctMarkJoin;
 pub use rule_push_down_filter_aggregate::RulePushDownFilterAggregate;
 pub use rule_push_down_filter_eval_scalar::RulePushDownFilterEvalScalar;
 pub use rule_push_down_filter_join::try_push_down_filter_join;
@@ -15,13 +15,16 @@
 use std::sync::Arc;
 
 use databend_common_exception::Result;
+use databend_common_expression::Scalar;
 use itertools::Itertools;
 
 use crate::optimizer::rule::Rule;
 use crate::optimizer::rule::RuleID;
 use crate::optimizer::rule::TransformResult;
 use crate::optimizer::SExpr;
+use crate::plans::ConstantExpr;
 use crate::plans::Filter;
+use crate::plans::FunctionCall;
 use crate::plans::PatternPlan;
 use crate::plans::RelOp;
 use crate::plans::ScalarExpr;
@@ -70,30 +73,31 @@ impl Rule for RuleEliminateFilter {
             .into_iter()
             .unique()
             .collect::<Vec<ScalarExpr>>();
-        // Delete identically equal predicate
+        // Delete identically equal predicate, and replace `col <> col` with `FALSE`
         // After constant fold is ready, we can delete the following code
         let predicates = predicates
             .into_iter()
-            .filter(|predicate| match predicate {
-                ScalarExpr::FunctionCall(func) if func.func_name == "eq" => {
-                    if let (
-                        ScalarExpr::BoundColumnRef(left_col),
-                        ScalarExpr::BoundColumnRef(right_col),
-                    ) = (&func.arguments[0], &func.arguments[1])
-                    {
-                        left_col.column.index != right_col.column.index
-                            || left_col.column.data_type.is_nullable()
-                    } else {
-                        true
+            .filter_map(|predicate| match &predicate {
+                ScalarExpr::FunctionCall(func) if is_self_comparison(func) => {
+                    match func.func_name.as_str() {
+                        "eq" => None,
+                        "noteq" => Some(
+                            ConstantExpr {
+                                span: func.span,
+                                value: Scalar::Boolean(false),
+                            }
+                            .into(),
+                        ),
+                        _ => Some(predicate),
                     }
                 }
-                _ => true,
+                _ => Some(predicate),
             })
             .collect::<Vec<ScalarExpr>>();
 
         if predicates.is_empty() {
             state.add_result(s_expr.child(0)?.clone());
-        } else if origin_predicates.len() != predicates.len() {
+        } else if origin_predicates != predicates {
             let filter = Filter { predicates };
             state.add_result(SExpr::create_unary(
                 Arc::new(filter.into()),
@@ -107,3 +111,18 @@ impl Rule for RuleEliminateFilter {
         &self.patterns
     }
 }
+
+/// Check if the function compares a non-nullable column with itself. A nullable
+/// column is not, since `NULL = NULL` is `NULL` rather than `TRUE`.
+fn is_self_comparison(func: &FunctionCall) -> bool {
+    match func.arguments.as_slice() {
+        [
+            ScalarExpr::BoundColumnRef(left_col),
+            ScalarExpr::BoundColumnRef(right_col),
+        ] => {
+            left_col.column.index == right_col.column.index
+                && !left_col.column.data_type.is_nullable_or_null()
+        }
+        _ => false,
+    }
+}
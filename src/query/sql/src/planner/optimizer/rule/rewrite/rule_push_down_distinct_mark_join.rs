@@ -0,0 +1,182 @@
+// Copyright 2021 Datafuse Labs
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use databend_common_exception::Result;
+
+use crate::optimizer::rule::Rule;
+use crate::optimizer::rule::RuleID;
+use crate::optimizer::rule::TransformResult;
+use crate::optimizer::RelExpr;
+use crate::optimizer::SExpr;
+use crate::plans::Aggregate;
+use crate::plans::AggregateMode;
+use crate::plans::BoundColumnRef;
+use crate::plans::Join;
+use crate::plans::JoinType;
+use crate::plans::PatternPlan;
+use crate::plans::RelOp;
+use crate::plans::RelOperator;
+use crate::plans::ScalarItem;
+use crate::plans::Visitor;
+
+/// Input:  RightMark Join
+///           /  \
+///          *    *
+///
+/// Output: RightMark Join
+///           /  \
+///          *    Aggregate(distinct keys)
+///                \
+///                 *
+///
+/// The marker of `a IN (SELECT b FROM t)` only depends on whether a matching `b`
+/// exists, so duplicated keys of the build side can be removed before they are
+/// inserted into the hash table. Only uncorrelated mark joins with equi conditions
+/// whose build side is not aggregated yet are rewritten.
+///
+/// Mark joins under a filter on the marker are converted into semi joins by
+/// `PushDownFilterJoin`, so this rule runs after the default rewrite rules.
+pub struct RulePushDownDistinctMarkJoin {
+    id: RuleID,
+    patterns: Vec<SExpr>,
+}
+
+impl RulePushDownDistinctMarkJoin {
+    pub fn new() -> Self {
+        Self {
+            id: RuleID::PushDownDistinctMarkJoin,
+            patterns: vec![SExpr::create_binary(
+                Arc::new(
+                    PatternPlan {
+                        plan_type: RelOp::Join,
+                    }
+                    .into(),
+                ),
+                Arc::new(SExpr::create_leaf(Arc::new(
+                    PatternPlan {
+                        plan_type: RelOp::Pattern,
+                    }
+                    .into(),
+                ))),
+                Arc::new(SExpr::create_leaf(Arc::new(
+                    PatternPlan {
+                        plan_type: RelOp::Pattern,
+                    }
+                    .into(),
+                ))),
+            )],
+        }
+    }
+}
+
+impl Rule for RulePushDownDistinctMarkJoin {
+    fn id(&self) -> RuleID {
+        self.id
+    }
+
+    fn apply(&self, s_expr: &SExpr, state: &mut TransformResult) -> Result<()> {
+        let join: Join = s_expr.plan().clone().try_into()?;
+        if join.join_type != JoinType::RightMark
+            || join.from_correlated_subquery
+            || join.right_conditions.is_empty()
+            || !join.non_equi_conditions.is_empty()
+        {
+            return Ok(());
+        }
+
+        let build = s_expr.child(1)?;
+        if is_aggregated(build) {
+            return Ok(());
+        }
+
+        let mut collector = ColumnRefCollector { columns: vec![] };
+        for condition in join.right_conditions.iter() {
+            collector.visit(condition)?;
+        }
+        let prop = RelExpr::with_s_expr(build).derive_relational_prop()?;
+        if collector.columns.is_empty()
+            || collector
+                .columns
+                .iter()
+                .any(|column| !prop.output_columns.contains(&column.column.index))
+        {
+            return Ok(());
+        }
+
+        let distinct = Aggregate {
+            mode: AggregateMode::Initial,
+            group_items: collector
+                .columns
+                .into_iter()
+                .map(|column| ScalarItem {
+                    index: column.column.index,
+                    scalar: column.into(),
+                })
+                .collect(),
+            aggregate_functions: vec![],
+            from_distinct: true,
+            limit: None,
+            grouping_sets: None,
+        };
+        let result = SExpr::create_binary(
+            Arc::new(join.into()),
+            Arc::new(s_expr.child(0)?.clone()),
+            Arc::new(SExpr::create_unary(
+                Arc::new(distinct.into()),
+                Arc::new(build.clone()),
+            )),
+        );
+        state.add_result(result);
+        Ok(())
+    }
+
+    fn patterns(&self) -> &Vec<SExpr> {
+        &self.patterns
+    }
+}
+
+/// Check if the plan already ends with an aggregate, optionally topped by operators
+/// that never add rows (projections, filters, sorts and limits).
+fn is_aggregated(s_expr: &SExpr) -> bool {
+    match s_expr.plan() {
+        RelOperator::EvalScalar(_)
+        | RelOperator::Filter(_)
+        | RelOperator::Sort(_)
+        | RelOperator::Limit(_) => match s_expr.child(0) {
+            Ok(child) => is_aggregated(child),
+            Err(_) => false,
+        },
+        RelOperator::Aggregate(_) => true,
+        _ => false,
+    }
+}
+
+struct ColumnRefCollector {
+    columns: Vec<BoundColumnRef>,
+}
+
+impl<'a> Visitor<'a> for ColumnRefCollector {
+    fn visit_bound_column_ref(&mut self, col: &'a BoundColumnRef) -> Result<()> {
+        if !self
+            .columns
+            .iter()
+            .any(|column| column.column.index == col.column.index)
+        {
+            self.columns.push(col.clone());
+        }
+        Ok(())
+    }
+}
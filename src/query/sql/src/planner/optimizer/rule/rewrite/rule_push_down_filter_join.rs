@@ -152,6 +152,9 @@ pub fn try_push_down_filter_join(
                 left_push_down.push(predicate.clone());
                 right_push_down.push(predicate.clone());
             }
+            // For a mark join built from a subquery, the marker is produced by the join
+            // itself, so predicates on the marker end up in `Other` and stay above the join,
+            // while predicates on the outer columns are pushed below it.
             JoinPredicate::Left(_) => {
                 if matches!(
                     join.join_type,
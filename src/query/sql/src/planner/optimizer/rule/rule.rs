@@ -94,6 +94,7 @@ pub enum RuleID {
     PushDownLimitAggregate,
     PushDownLimitScan,
     PushDownSortScan,
+    PushDownDistinctMarkJoin,
     SemiToInnerJoin,
     EliminateEvalScalar,
     EliminateFilter,
@@ -130,6 +131,7 @@ impl Display for RuleID {
             RuleID::PushDownLimitScan => write!(f, "PushDownLimitScan"),
             RuleID::PushDownSortScan => write!(f, "PushDownSortScan"),
             RuleID::PushDownFilterWindow => write!(f, "PushDownFilterWindow"),
+            RuleID::PushDownDistinctMarkJoin => write!(f, "PushDownDistinctMarkJoin"),
             RuleID::EliminateEvalScalar => write!(f, "EliminateEvalScalar"),
             RuleID::EliminateFilter => write!(f, "EliminateFilter"),
             RuleID::EliminateSort => write!(f, "EliminateSort"),
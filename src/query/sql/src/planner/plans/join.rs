@@ -20,7 +20,9 @@ use std::sync::Arc;
 
 use databend_common_catalog::table_context::TableContext;
 use databend_common_exception::Result;
+use databend_common_expression::types::DataType;
 use databend_common_expression::types::F64;
+use databend_common_expression::DataField;
 use databend_common_storage::Datum;
 
 use crate::optimizer::histogram_from_ndv;
@@ -171,6 +173,38 @@ impl Default for Join {
 }
 
 impl Join {
+    /// The marker column of a mark join, named after `marker_index`.
+    pub fn marker_field(&self) -> DataField {
+        let name = match self.marker_index {
+            Some(idx) => idx.to_string(),
+            None => "marker".to_string(),
+        };
+        DataField::new(&name, DataType::Nullable(Box::new(DataType::Boolean)))
+    }
+
+    /// Output fields of the join, given the output fields of the left (probe) and the
+    /// right (build) child. Fields of a side that can be padded with NULLs become
+    /// nullable, and mark joins append the marker after the fields of the side they keep.
+    pub fn output_fields(&self, left: &[DataField], right: &[DataField]) -> Vec<DataField> {
+        fn nullable(fields: &[DataField]) -> Vec<DataField> {
+            fields
+                .iter()
+                .map(|field| DataField::new(field.name(), field.data_type().wrap_nullable()))
+                .collect()
+        }
+
+        match self.join_type {
+            JoinType::Cross | JoinType::Inner => [left, right].concat(),
+            JoinType::Left | JoinType::LeftSingle => [left.to_vec(), nullable(right)].concat(),
+            JoinType::Right | JoinType::RightSingle => [nullable(left), right.to_vec()].concat(),
+            JoinType::Full => [nullable(left), nullable(right)].concat(),
+            JoinType::LeftSemi | JoinType::LeftAnti => left.to_vec(),
+            JoinType::RightSemi | JoinType::RightAnti => right.to_vec(),
+            JoinType::LeftMark => [right.to_vec(), vec![self.marker_field()]].concat(),
+            JoinType::RightMark => [left.to_vec(), vec![self.marker_field()]].concat(),
+        }
+    }
+
     pub fn used_columns(&self) -> Result<ColumnSet> {
         let mut used_columns = ColumnSet::new();
         for cond in self
@@ -542,7 +542,9 @@ pub struct CastExpr {
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum SubqueryType {
+    /// `expr op ANY(..)`, also used for its synonym `expr op SOME(..)` and for `IN`.
     Any,
+    /// Reserved, `expr op ALL(..)` is bound as `NOT (expr contrary_op ANY(..))`.
     All,
     Scalar,
     Exists,
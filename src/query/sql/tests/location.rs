@@ -15,6 +15,7 @@
 #![allow(clippy::uninlined_format_args)]
 
 mod optimizer;
+mod plans;
 
 use std::collections::BTreeMap;
 
@@ -0,0 +1,95 @@
+// Copyright 2023 Datafuse Labs.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use databend_common_expression::types::DataType;
+use databend_common_expression::types::NumberDataType;
+use databend_common_expression::DataField;
+use databend_common_sql::plans::Join;
+use databend_common_sql::plans::JoinType;
+
+fn output_fields(join_type: JoinType, marker_index: Option<usize>) -> Vec<(String, DataType)> {
+    let int = DataType::Number(NumberDataType::Int32);
+    let left = vec![DataField::new("a", int.clone())];
+    let right = vec![
+        DataField::new("b", int.clone()),
+        DataField::new("c", int.wrap_nullable()),
+    ];
+    let join = Join {
+        join_type,
+        marker_index,
+        ..Default::default()
+    };
+    join.output_fields(&left, &right)
+        .into_iter()
+        .map(|field| (field.name().clone(), field.data_type().clone()))
+        .collect()
+}
+
+#[test]
+fn test_join_output_fields() {
+    let int = DataType::Number(NumberDataType::Int32);
+    let nullable_int = int.wrap_nullable();
+    let marker = DataType::Nullable(Box::new(DataType::Boolean));
+    let field = |name: &str, data_type: &DataType| (name.to_string(), data_type.clone());
+
+    let all = vec![
+        field("a", &int),
+        field("b", &int),
+        field("c", &nullable_int),
+    ];
+    assert_eq!(output_fields(JoinType::Cross, None), all);
+    assert_eq!(output_fields(JoinType::Inner, None), all);
+
+    let right_nullable = vec![
+        field("a", &int),
+        field("b", &nullable_int),
+        field("c", &nullable_int),
+    ];
+    assert_eq!(output_fields(JoinType::Left, None), right_nullable);
+    assert_eq!(output_fields(JoinType::LeftSingle, None), right_nullable);
+
+    let left_nullable = vec![
+        field("a", &nullable_int),
+        field("b", &int),
+        field("c", &nullable_int),
+    ];
+    assert_eq!(output_fields(JoinType::Right, None), left_nullable);
+    assert_eq!(output_fields(JoinType::RightSingle, None), left_nullable);
+
+    assert_eq!(output_fields(JoinType::Full, None), vec![
+        field("a", &nullable_int),
+        field("b", &nullable_int),
+        field("c", &nullable_int),
+    ]);
+
+    assert_eq!(output_fields(JoinType::LeftSemi, None), vec![field(
+        "a", &int
+    )]);
+    assert_eq!(output_fields(JoinType::LeftAnti, None), vec![field(
+        "a", &int
+    )]);
+    let right = vec![field("b", &int), field("c", &nullable_int)];
+    assert_eq!(output_fields(JoinType::RightSemi, None), right);
+    assert_eq!(output_fields(JoinType::RightAnti, None), right);
+
+    assert_eq!(output_fields(JoinType::RightMark, Some(7)), vec![
+        field("a", &int),
+        field("7", &marker),
+    ]);
+    assert_eq!(output_fields(JoinType::LeftMark, None), vec![
+        field("b", &int),
+        field("c", &nullable_int),
+        field("marker", &marker),
+    ]);
+}